@@ -1,6 +1,12 @@
-use crate::document::{Document, RawDocument};
+use crate::document::{Document, RawDocument, RawResource};
 use crate::included::IncludedMap;
+use crate::link::resolve_link_values;
+use crate::resource::ResourceDescriptor;
+use crate::validate::validate_document_value;
+use http::Uri;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+use std::collections::{HashSet, VecDeque};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -8,7 +14,7 @@ pub enum Error {
     #[error("Invalid type")]
     InvalidType(&'static str),
 
-    #[error("Document contains neither data nor error")]
+    #[error("Document contains neither data, errors nor meta")]
     IncompleteDocument,
 
     #[error("Missing ID")]
@@ -29,15 +35,118 @@ pub enum Error {
     #[error("Missing resource")]
     MissingResource { kind: String, id: String },
 
+    #[error("Duplicate resource")]
+    DuplicateResource { kind: String, id: String },
+
     #[error("Resource type mismatch")]
     ResourceTypeMismatch { expected: String, found: String },
 
     #[error(transparent)]
     SerdeError(#[from] serde_json::Error),
+
+    /// A validation hook rejected the value at `pointer`.
+    #[error("Validation failed: {message}")]
+    Validation { pointer: String, message: String },
+
+    #[error("Multiple errors")]
+    Multiple(Vec<LocatedError>),
+
+    /// Stands in for an error which has been recorded while accumulating errors. It is always
+    /// replaced by all recorded errors before the deserialization returns.
+    #[doc(hidden)]
+    #[error("Error has been reported")]
+    Reported(Reported),
+}
+
+/// Marks an error as recorded. It can only be created within this crate, so that no other error
+/// is mistaken for a recorded one.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Reported(());
+
+impl Error {
+    #[doc(hidden)]
+    pub fn reported() -> Self {
+        Error::Reported(Reported(()))
+    }
+
+    #[doc(hidden)]
+    pub fn is_reported(&self) -> bool {
+        matches!(self, Error::Reported(_))
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("{error} at {pointer}")]
+pub struct LocatedError {
+    /// JSON pointer to the offending value within the document.
+    pub pointer: String,
+    #[source]
+    pub error: Error,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct DeserializeOptions {
+    /// Whether to walk the entire document and report all errors, instead of failing on the first
+    /// one. All errors are returned together as [`Error::Multiple`], including those of included
+    /// resources which are never referenced.
+    pub accumulate_errors: bool,
+    /// Whether to check the document against the specification before deserializing it. All
+    /// violations are returned together as [`crate::Error::SpecViolation`].
+    pub validate: bool,
+    /// How to handle multiple resources with the same type and ID. Duplicates are reported as
+    /// [`Warning`]s on the document unless they are rejected.
+    pub duplicates: DuplicatePolicy,
+    /// Whether to report the included resources which were never resolved through a relationship
+    /// in [`Document::unused_included`].
+    pub report_unused_included: bool,
+    /// The URI the document was requested from. When given, the links of the document, its
+    /// resources, their relationships and its errors are resolved against it.
+    pub base_uri: Option<Uri>,
+}
+
+/// How to handle multiple resources with the same type and ID.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum DuplicatePolicy {
+    /// Fail with [`Error::DuplicateResource`].
+    Error,
+    /// Resolve relationships to the first of the included resources.
+    FirstWins,
+    /// Resolve relationships to the last of the included resources.
+    #[default]
+    LastWins,
+    /// Resolve relationships to a merge of all included resources, where objects are merged
+    /// recursively and later values take precedence.
+    DeepMerge,
+}
+
+/// A problem with a document which did not prevent it from being deserialized.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// The resource at `pointer` has the same type and ID as an earlier one.
+    DuplicateResource {
+        kind: String,
+        id: String,
+        pointer: String,
+    },
 }
 
 pub trait JsonApiDeserialize: Sized {
+    /// The descriptor of the resource, for types which also implement
+    /// [`crate::JsonApiResource`].
+    ///
+    /// Relationship descriptors only link to resources which provide it, so hand-written
+    /// resources can opt into reflection by setting it to `Some(Self::descriptor)`.
+    const DESCRIPTOR: Option<fn() -> &'static ResourceDescriptor> = None;
+
     fn from_value(value: &Value, included: &mut IncludedMap) -> Result<Self, Error>;
+
+    /// Returns the primary data of a meta-only document, which only types that do not expect any
+    /// data can provide.
+    fn without_data() -> Option<Self> {
+        None
+    }
 }
 
 impl<T> JsonApiDeserialize for Option<T>
@@ -53,41 +162,316 @@ where
     }
 }
 
+/// Primary data of documents without any, such as meta-only documents.
+impl JsonApiDeserialize for () {
+    fn from_value(value: &Value, _: &mut IncludedMap) -> Result<Self, Error> {
+        match value {
+            Value::Null => Ok(()),
+            _ => Err(Error::InvalidType("Expected null")),
+        }
+    }
+
+    fn without_data() -> Option<Self> {
+        Some(())
+    }
+}
+
 impl<T> JsonApiDeserialize for Vec<T>
 where
     T: JsonApiDeserialize,
 {
     fn from_value(value: &Value, included: &mut IncludedMap) -> Result<Self, Error> {
-        value
+        let values = value
             .as_array()
-            .ok_or(Error::InvalidType("Expected an array"))?
-            .iter()
-            .map(|value| T::from_value(value, included))
-            .collect()
+            .ok_or(Error::InvalidType("Expected an array"))?;
+        let mut items = Vec::with_capacity(values.len());
+        let mut failed = false;
+
+        for (index, value) in values.iter().enumerate() {
+            match included.scoped(&format!("/{}", index), |included| {
+                T::from_value(value, included)
+            }) {
+                Ok(item) => items.push(item),
+                Err(error) if error.is_reported() => failed = true,
+                Err(error) => return Err(error),
+            }
+        }
+
+        if failed {
+            return Err(Error::reported());
+        }
+
+        Ok(items)
+    }
+}
+
+impl<T> JsonApiDeserialize for VecDeque<T>
+where
+    T: JsonApiDeserialize,
+{
+    fn from_value(value: &Value, included: &mut IncludedMap) -> Result<Self, Error> {
+        Vec::from_value(value, included).map(Into::into)
+    }
+}
+
+impl<T> JsonApiDeserialize for Box<[T]>
+where
+    T: JsonApiDeserialize,
+{
+    fn from_value(value: &Value, included: &mut IncludedMap) -> Result<Self, Error> {
+        Vec::from_value(value, included).map(Into::into)
+    }
+}
+
+/// Captures the error of a single value instead of failing, which allows collections to be
+/// deserialized partially, e.g. as `Vec<Result<T, Error>>`.
+impl<T> JsonApiDeserialize for Result<T, Error>
+where
+    T: JsonApiDeserialize,
+{
+    fn from_value(value: &Value, included: &mut IncludedMap) -> Result<Self, Error> {
+        Ok(included.without_accumulation(|included| T::from_value(value, included)))
     }
 }
 
 pub fn deserialize_document<T: JsonApiDeserialize>(
     json: &str,
 ) -> Result<Document<T>, crate::error::Error> {
+    deserialize_document_with_options(json, &DeserializeOptions::default())
+}
+
+/// Deserializes a collection document, keeping all resources which could be deserialized.
+///
+/// Every item of the primary data is either the deserialized resource or the error which
+/// prevented it from being deserialized.
+pub fn deserialize_document_partial<T: JsonApiDeserialize>(
+    json: &str,
+) -> Result<Document<Vec<Result<T, Error>>>, crate::error::Error> {
+    deserialize_document(json)
+}
+
+/// Deserializes a document according to the given options.
+///
+/// The top-level `meta` is deserialized into `M`, which is inferred from the returned document.
+pub fn deserialize_document_with_options<T: JsonApiDeserialize, M: DeserializeOwned>(
+    json: &str,
+    options: &DeserializeOptions,
+) -> Result<Document<T, M>, crate::error::Error> {
+    deserialize_document_str(json, options)
+}
+
+/// Deserializes a meta-only document, returning its top-level `meta`.
+pub fn deserialize_meta_document<M: DeserializeOwned>(
+    json: &str,
+) -> Result<M, crate::error::Error> {
+    let document: Document<(), M> =
+        deserialize_document_with_options(json, &DeserializeOptions::default())?;
+    Ok(document.meta.ok_or(Error::IncompleteDocument)?)
+}
+
+/// Deserializes a document which has already been parsed, e.g. because it is embedded in a larger
+/// JSON value.
+pub fn deserialize_document_value<T: JsonApiDeserialize>(
+    value: Value,
+) -> Result<Document<T>, crate::error::Error> {
+    deserialize_document_value_with_options(value, &DeserializeOptions::default())
+}
+
+pub fn deserialize_document_value_with_options<T: JsonApiDeserialize, M: DeserializeOwned>(
+    value: Value,
+    options: &DeserializeOptions,
+) -> Result<Document<T, M>, crate::error::Error> {
+    deserialize_parsed_document(value, options)
+}
+
+fn deserialize_document_str<T: JsonApiDeserialize, M: DeserializeOwned>(
+    json: &str,
+    options: &DeserializeOptions,
+) -> Result<Document<T, M>, crate::error::Error> {
+    // Validation needs the document as a value, otherwise it is parsed into the raw document
+    // directly.
+    if options.validate {
+        let value: Value = serde_json::from_str(json).map_err(Error::SerdeError)?;
+        return deserialize_parsed_document(value, options);
+    }
+
     let raw_document: RawDocument = serde_json::from_str(json).map_err(Error::SerdeError)?;
-    let mut included_map: IncludedMap = match raw_document.included {
-        Some(ref resources) => resources.into(),
-        None => Default::default(),
-    };
+    deserialize_raw_document(raw_document, options)
+}
 
+fn deserialize_parsed_document<T: JsonApiDeserialize, M: DeserializeOwned>(
+    value: Value,
+    options: &DeserializeOptions,
+) -> Result<Document<T, M>, crate::error::Error> {
+    if options.validate {
+        let violations = validate_document_value(&value);
+
+        if !violations.is_empty() {
+            return Err(crate::error::Error::SpecViolation(violations));
+        }
+    }
+
+    let raw_document: RawDocument = serde_json::from_value(value).map_err(Error::SerdeError)?;
+    deserialize_raw_document(raw_document, options)
+}
+
+pub(crate) fn deserialize_raw_document<T: JsonApiDeserialize, M: DeserializeOwned>(
+    raw_document: RawDocument,
+    options: &DeserializeOptions,
+) -> Result<Document<T, M>, crate::error::Error> {
     if let Some(errors) = raw_document.errors {
-        return Err(crate::error::Error::DocumentError(errors));
+        let error = crate::error::Error::DocumentError(errors, None);
+
+        return Err(match &options.base_uri {
+            Some(base) => error.with_base_uri(base),
+            None => error,
+        });
+    }
+
+    let mut raw_data = match (raw_document.data, &raw_document.meta) {
+        (Some(data), _) => Some(data),
+        (None, Some(_)) => None,
+        (None, None) => return Err(Error::IncompleteDocument.into()),
+    };
+    let mut resources = raw_document.included.unwrap_or_default();
+    let mut links = raw_document.links;
+
+    if let Some(base) = &options.base_uri {
+        match &mut raw_data {
+            Some(Value::Array(items)) => items
+                .iter_mut()
+                .for_each(|item| resolve_resource_links(item, base)),
+            Some(data) => resolve_resource_links(data, base),
+            None => {}
+        }
+
+        for resource in &mut resources {
+            if let Some(links) = &mut resource.links {
+                resolve_link_values(links, base);
+            }
+
+            if let Some(relationships) = &mut resource.relationships {
+                resolve_relationship_links(relationships, base);
+            }
+        }
+
+        links = links.map(|links| links.with_base_uri(base));
+    }
+
+    let warnings = find_duplicates(raw_data.as_ref().unwrap_or(&Value::Null), &resources);
+
+    if let (DuplicatePolicy::Error, Some(Warning::DuplicateResource { kind, id, .. })) =
+        (options.duplicates, warnings.first())
+    {
+        return Err(Error::DuplicateResource {
+            kind: kind.clone(),
+            id: id.clone(),
+        }
+        .into());
+    }
+
+    let mut included_map = IncludedMap::new(resources, options.duplicates);
+
+    if options.accumulate_errors {
+        included_map.accumulate_errors();
+    }
+
+    // A meta-only document only has primary data for types which do not expect any.
+    let data = match &raw_data {
+        Some(raw_data) => included_map.scoped("/data", |included_map| {
+            T::from_value(raw_data, included_map)
+        }),
+        None => T::without_data().ok_or(Error::IncompleteDocument),
+    };
+    let meta = match raw_document.meta {
+        Some(meta) => included_map
+            .scoped("/meta", |_| Ok(serde_json::from_value(meta)?))
+            .map(Some),
+        None => Ok(None),
+    };
+    let unused_included = options
+        .report_unused_included
+        .then(|| included_map.unused());
+
+    if included_map.is_accumulating() {
+        included_map.resolve_remaining();
+    }
+
+    let errors = included_map.take_errors();
+
+    match (data, meta) {
+        // Errors are either all recorded, or the first one is returned right away.
+        (Err(error), _) | (_, Err(error)) if !error.is_reported() => Err(error.into()),
+        (Ok(data), Ok(meta)) if errors.is_empty() => Ok(Document {
+            data,
+            meta,
+            links,
+            warnings,
+            unused_included,
+        }),
+        _ => Err(Error::Multiple(errors).into()),
+    }
+}
+
+fn resolve_resource_links(resource: &mut Value, base: &Uri) {
+    if let Value::Object(resource) = resource {
+        if let Some(links) = resource.get_mut("links") {
+            resolve_link_values(links, base);
+        }
+
+        if let Some(relationships) = resource.get_mut("relationships") {
+            resolve_relationship_links(relationships, base);
+        }
     }
+}
+
+fn resolve_relationship_links(relationships: &mut Value, base: &Uri) {
+    if let Value::Object(relationships) = relationships {
+        for links in relationships
+            .values_mut()
+            .filter_map(|relationship| relationship.get_mut("links"))
+        {
+            resolve_link_values(links, base);
+        }
+    }
+}
+
+/// Reports every resource which has the same type and ID as an earlier one in the primary data or
+/// the included resources.
+fn find_duplicates(data: &Value, included: &[RawResource]) -> Vec<Warning> {
+    let primary = match data {
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (format!("/data/{}", index), item))
+            .collect(),
+        Value::Object(_) => vec![("/data".to_string(), data)],
+        _ => Vec::new(),
+    };
+    let primary = primary.into_iter().filter_map(|(pointer, item)| {
+        Some((
+            pointer,
+            item.get("type")?.as_str()?,
+            item.get("id")?.as_str()?,
+        ))
+    });
+    let included = included.iter().enumerate().map(|(index, resource)| {
+        (
+            format!("/included/{}", index),
+            resource.kind.as_str(),
+            resource.id.as_str(),
+        )
+    });
 
-    let data = T::from_value(
-        &raw_document.data.ok_or(Error::IncompleteDocument)?,
-        &mut included_map,
-    )?;
+    let mut seen = HashSet::new();
 
-    Ok(Document {
-        data,
-        meta: raw_document.meta,
-        links: raw_document.links,
-    })
+    primary
+        .chain(included)
+        .filter(|(_, kind, id)| !seen.insert((*kind, *id)))
+        .map(|(pointer, kind, id)| Warning::DuplicateResource {
+            kind: kind.to_string(),
+            id: id.to_string(),
+            pointer,
+        })
+        .collect()
 }
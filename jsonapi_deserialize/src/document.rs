@@ -1,17 +1,44 @@
-use crate::deserialize::JsonApiDeserialize;
+use crate::deserialize::{Error, JsonApiDeserialize, Warning};
+use crate::included::IncludedMap;
 use crate::link::Link;
-use serde::Deserialize;
+use http::Uri;
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
+/// A deserialized document, with its top-level `meta` deserialized into `M`.
 #[derive(Debug)]
-pub struct Document<T>
+pub struct Document<T, M = HashMap<String, Value>>
 where
     T: JsonApiDeserialize,
 {
     pub data: T,
-    pub meta: Option<HashMap<String, Value>>,
+    pub meta: Option<M>,
     pub links: Option<DocumentLinks>,
+    /// Problems with the document which did not prevent it from being deserialized.
+    pub warnings: Vec<Warning>,
+    /// The included resources which were never resolved, when requested through
+    /// [`crate::DeserializeOptions::report_unused_included`].
+    pub unused_included: Option<Vec<Reference>>,
+}
+
+impl<T, M> Document<T, M>
+where
+    T: JsonApiDeserialize,
+{
+    /// Returns the document with its top-level links resolved against a base URI.
+    ///
+    /// The base URI would usually be the URI the document was requested from. To also resolve
+    /// the links of resources and relationships, pass it as
+    /// [`crate::DeserializeOptions::base_uri`] instead.
+    pub fn with_base_uri(mut self, base: &Uri) -> Self {
+        self.links = self.links.map(|links| links.with_base_uri(base));
+        self
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,6 +53,22 @@ pub struct DocumentError {
     pub meta: Option<HashMap<String, Value>>,
 }
 
+impl DocumentError {
+    /// Deserializes the `meta` of the error into `M`.
+    pub fn meta_as<M: DeserializeOwned>(&self) -> Result<Option<M>, serde_json::Error> {
+        self.meta
+            .as_ref()
+            .map(|meta| M::deserialize(Value::Object(meta.clone().into_iter().collect())))
+            .transpose()
+    }
+
+    /// Returns the error with all of its links resolved against a base URI.
+    pub fn with_base_uri(mut self, base: &Uri) -> Self {
+        self.links = self.links.map(|links| links.with_base_uri(base));
+        self
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ErrorLinks {
     pub about: Option<Link>,
@@ -33,6 +76,16 @@ pub struct ErrorLinks {
     pub kind: Option<Link>,
 }
 
+impl ErrorLinks {
+    /// Returns the links with their `href` resolved against a base URI.
+    pub fn with_base_uri(self, base: &Uri) -> Self {
+        Self {
+            about: self.about.map(|link| link.with_base_uri(base)),
+            kind: self.kind.map(|link| link.with_base_uri(base)),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ErrorSource {
     pub pointer: Option<String>,
@@ -40,8 +93,7 @@ pub struct ErrorSource {
     pub header: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
+#[derive(Debug, Default, Deserialize)]
 pub struct DocumentLinks {
     #[serde(rename = "self")]
     pub this: Option<Link>,
@@ -52,31 +104,248 @@ pub struct DocumentLinks {
     pub last: Option<Link>,
     pub prev: Option<Link>,
     pub next: Option<Link>,
+    #[serde(default, deserialize_with = "deserialize_link_list")]
+    pub profile: Vec<Link>,
+    /// Any links not covered by the members above, in document order.
+    #[serde(flatten)]
+    pub other: IndexMap<String, Option<Link>>,
+}
+
+impl DocumentLinks {
+    /// Returns the link with the given name, including the ones which are not known to the
+    /// specification.
+    ///
+    /// As `profile` is a list of links, it is not accessible through this method.
+    pub fn get(&self, rel: &str) -> Option<&Link> {
+        match rel {
+            "self" => self.this.as_ref(),
+            "related" => self.related.as_ref(),
+            "describedby" => self.described_by.as_ref(),
+            "first" => self.first.as_ref(),
+            "last" => self.last.as_ref(),
+            "prev" => self.prev.as_ref(),
+            "next" => self.next.as_ref(),
+            rel => self.other.get(rel).and_then(Option::as_ref),
+        }
+    }
+
+    /// Returns the links with their `href` resolved against a base URI.
+    pub fn with_base_uri(self, base: &Uri) -> Self {
+        Self {
+            this: self.this.map(|link| link.with_base_uri(base)),
+            related: self.related.map(|link| link.with_base_uri(base)),
+            described_by: self.described_by.map(|link| link.with_base_uri(base)),
+            first: self.first.map(|link| link.with_base_uri(base)),
+            last: self.last.map(|link| link.with_base_uri(base)),
+            prev: self.prev.map(|link| link.with_base_uri(base)),
+            next: self.next.map(|link| link.with_base_uri(base)),
+            profile: self
+                .profile
+                .into_iter()
+                .map(|link| link.with_base_uri(base))
+                .collect(),
+            other: resolve_other_links(self.other, base),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ResourceLinks {
+    #[serde(rename = "self")]
+    pub this: Option<Link>,
+    /// Any links not covered by the members above, in document order.
+    #[serde(flatten)]
+    pub other: IndexMap<String, Option<Link>>,
+}
+
+impl ResourceLinks {
+    /// Returns the link with the given name, including the ones which are not known to the
+    /// specification.
+    pub fn get(&self, rel: &str) -> Option<&Link> {
+        match rel {
+            "self" => self.this.as_ref(),
+            rel => self.other.get(rel).and_then(Option::as_ref),
+        }
+    }
+
+    /// Returns the links with their `href` resolved against a base URI.
+    pub fn with_base_uri(self, base: &Uri) -> Self {
+        Self {
+            this: self.this.map(|link| link.with_base_uri(base)),
+            other: resolve_other_links(self.other, base),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RelationshipLinks {
+    #[serde(rename = "self")]
+    pub this: Option<Link>,
+    pub related: Option<Link>,
+    /// Any links not covered by the members above, in document order.
+    #[serde(flatten)]
+    pub other: IndexMap<String, Option<Link>>,
+}
+
+impl RelationshipLinks {
+    /// Returns the link with the given name, including the ones which are not known to the
+    /// specification.
+    pub fn get(&self, rel: &str) -> Option<&Link> {
+        match rel {
+            "self" => self.this.as_ref(),
+            "related" => self.related.as_ref(),
+            rel => self.other.get(rel).and_then(Option::as_ref),
+        }
+    }
+
+    /// Returns the links with their `href` resolved against a base URI.
+    pub fn with_base_uri(self, base: &Uri) -> Self {
+        Self {
+            this: self.this.map(|link| link.with_base_uri(base)),
+            related: self.related.map(|link| link.with_base_uri(base)),
+            other: resolve_other_links(self.other, base),
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Eq, PartialEq, Clone)]
+fn resolve_other_links(
+    links: IndexMap<String, Option<Link>>,
+    base: &Uri,
+) -> IndexMap<String, Option<Link>> {
+    links
+        .into_iter()
+        .map(|(rel, link)| (rel, link.map(|link| link.with_base_uri(base))))
+        .collect()
+}
+
+fn deserialize_link_list<'de, D>(deserializer: D) -> Result<Vec<Link>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Vec<Link>>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// A resource identifier object.
+///
+/// References are identified by their type and ID alone, `meta` is not taken into account when
+/// comparing or hashing them.
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Reference {
     pub id: String,
     #[serde(rename = "type")]
     pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<HashMap<String, Value>>,
+}
+
+impl Reference {
+    pub fn new(kind: impl Into<String>, id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            kind: kind.into(),
+            meta: None,
+        }
+    }
+}
+
+impl PartialEq for Reference {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.kind == other.kind
+    }
+}
+
+impl Eq for Reference {}
+
+impl PartialOrd for Reference {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Reference {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.id, &self.kind).cmp(&(&other.id, &other.kind))
+    }
+}
+
+impl Hash for Reference {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.kind.hash(state);
+    }
 }
 
+impl JsonApiDeserialize for Reference {
+    fn from_value(value: &Value, _included: &mut IncludedMap) -> Result<Self, Error> {
+        let reference: Self = serde_json::from_value(value.clone())?;
+        Ok(reference)
+    }
+}
+
+/// Keeps an explicit `null`, which is valid primary data, apart from an absent member.
+fn deserialize_present<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// A document with its primary data and included resources not yet deserialized.
 #[derive(Debug, Deserialize)]
-pub(crate) struct RawDocument {
+pub struct RawDocument {
+    #[serde(default, deserialize_with = "deserialize_present")]
     pub data: Option<Value>,
     pub errors: Option<Vec<DocumentError>>,
-    pub meta: Option<HashMap<String, Value>>,
+    pub meta: Option<Value>,
     pub links: Option<DocumentLinks>,
     pub included: Option<Vec<RawResource>>,
 }
 
-#[derive(Debug, Deserialize)]
-pub(crate) struct RawResource {
+/// An included resource which has not been deserialized yet.
+#[derive(Debug, Deserialize, Clone)]
+pub struct RawResource {
     pub id: String,
     #[serde(rename = "type")]
     pub kind: String,
     pub attributes: Option<Value>,
     pub relationships: Option<Value>,
+    pub links: Option<Value>,
+    pub meta: Option<Value>,
+}
+
+impl RawResource {
+    /// Merges the members of another occurrence of the same resource into this one.
+    pub(crate) fn merge(&mut self, other: RawResource) {
+        merge_member(&mut self.attributes, other.attributes);
+        merge_member(&mut self.relationships, other.relationships);
+        merge_member(&mut self.links, other.links);
+        merge_member(&mut self.meta, other.meta);
+    }
+}
+
+fn merge_member(target: &mut Option<Value>, source: Option<Value>) {
+    match (target.as_mut(), source) {
+        (Some(target), Some(source)) => merge_value(target, source),
+        (None, source) => *target = source,
+        (Some(_), None) => {}
+    }
+}
+
+/// Merges objects recursively, any other value is replaced.
+fn merge_value(target: &mut Value, source: Value) {
+    match (target, source) {
+        (Value::Object(target), Value::Object(source)) => {
+            for (key, value) in source {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, source) => *target = source,
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -111,6 +380,14 @@ impl<'a> From<&'a RawResource> for Value {
             value["relationships"] = rels_value;
         }
 
+        if let Some(links) = &resource.links {
+            value["links"] = links.clone();
+        }
+
+        if let Some(meta) = &resource.meta {
+            value["meta"] = meta.clone();
+        }
+
         value
     }
 }
@@ -1,10 +1,39 @@
+use http::{StatusCode, Uri};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
+    /// The document contains errors instead of data, together with the status of the response
+    /// it was received in, if known. Unsuccessful responses without a document have no errors.
     #[error("Document returned errors")]
-    DocumentError(Vec<crate::document::DocumentError>),
+    DocumentError(Vec<crate::document::DocumentError>, Option<StatusCode>),
 
     #[error(transparent)]
     DeserializeError(#[from] crate::deserialize::Error),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error("Document violates the specification")]
+    SpecViolation(Vec<crate::validate::SpecViolation>),
+
+    #[error(transparent)]
+    ResponseError(#[from] crate::response::Error),
+}
+
+impl Error {
+    /// Returns the error with the links of all contained document errors resolved against a base
+    /// URI.
+    pub fn with_base_uri(self, base: &Uri) -> Self {
+        match self {
+            Error::DocumentError(errors, status) => Error::DocumentError(
+                errors
+                    .into_iter()
+                    .map(|error| error.with_base_uri(base))
+                    .collect(),
+                status,
+            ),
+            error => error,
+        }
+    }
 }
@@ -1,62 +1,513 @@
-use crate::deserialize::{Error, JsonApiDeserialize};
-use crate::document::RawResource;
-use serde_json::Value;
+use crate::deserialize::{DuplicatePolicy, Error, JsonApiDeserialize, LocatedError};
+use crate::document::{RawResource, Reference};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
 use std::any::{Any, TypeId};
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
-struct Handle<T: ?Sized + Send + Sync + 'static>(Arc<T>);
+/// A pointer type through which an included resource is shared between relationships.
+pub trait SharedPointer<T>: Clone + 'static {
+    fn new(resource: T) -> Self;
 
+    /// Returns the pointer to the included resource at `index`, if it has been deserialized
+    /// before.
+    #[doc(hidden)]
+    fn cached(included_map: &IncludedMap, index: usize) -> Option<Self>;
+
+    /// Keeps the pointer to the included resource at `index` for later lookups.
+    #[doc(hidden)]
+    fn cache(&self, included_map: &mut IncludedMap, index: usize);
+}
+
+impl<T: Send + Sync + 'static> SharedPointer<T> for Arc<T> {
+    fn new(resource: T) -> Self {
+        Arc::new(resource)
+    }
+
+    fn cached(included_map: &IncludedMap, index: usize) -> Option<Self> {
+        let existing = included_map
+            .deserialized_map
+            .get(&(index, TypeId::of::<T>()))?;
+        existing.clone().downcast().ok()
+    }
+
+    fn cache(&self, included_map: &mut IncludedMap, index: usize) {
+        included_map
+            .deserialized_map
+            .insert((index, TypeId::of::<T>()), self.clone());
+    }
+}
+
+impl<T: 'static> SharedPointer<T> for Rc<T> {
+    fn new(resource: T) -> Self {
+        Rc::new(resource)
+    }
+
+    fn cached(included_map: &IncludedMap, index: usize) -> Option<Self> {
+        LOCAL_CACHE.with(|cache| {
+            cache
+                .borrow()
+                .get(&(included_map.id.0, index, TypeId::of::<T>()))
+                .and_then(|existing| existing.downcast_ref::<Rc<T>>())
+                .cloned()
+        })
+    }
+
+    fn cache(&self, included_map: &mut IncludedMap, index: usize) {
+        LOCAL_CACHE.with(|cache| {
+            cache.borrow_mut().insert(
+                (included_map.id.0, index, TypeId::of::<T>()),
+                Box::new(self.clone()),
+            )
+        });
+    }
+}
+
+thread_local! {
+    /// Resources behind pointers which are not `Send`, like `Rc`. Keeping them out of the
+    /// `IncludedMap` keeps it `Send`, so they are only shared until the outermost call into the
+    /// map returns.
+    static LOCAL_CACHE: RefCell<LocalCache> = RefCell::new(HashMap::new());
+}
+
+type LocalCache = HashMap<(u64, usize, TypeId), Box<dyn Any>>;
+
+/// Identifies the entries of an `IncludedMap` within the thread-local cache.
+struct MapId(u64);
+
+impl Default for MapId {
+    fn default() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        MapId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl MapId {
+    fn clear_local_cache(&self) {
+        // The resources are dropped outside of the borrow, in case dropping them touches the
+        // cache again.
+        let _removed = LOCAL_CACHE.try_with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let (removed, retained) = std::mem::take(&mut *cache)
+                .into_iter()
+                .partition::<LocalCache, _>(|((id, _, _), _)| *id == self.0);
+            *cache = retained;
+            removed
+        });
+    }
+}
+
+/// Drops the resources left behind when a deserialization panicked.
+impl Drop for MapId {
+    fn drop(&mut self) {
+        self.clear_local_cache();
+    }
+}
+
+/// A relationship field type which can be resolved from the included resources.
+///
+/// Shared pointers hand out the same allocation for every reference to a resource, while `Box<T>`
+/// and plain `T` receive a clone of it.
+pub trait FromIncluded<T>: Sized {
+    fn from_included(included_map: &mut IncludedMap, reference: &Reference) -> Result<Self, Error>;
+}
+
+impl<T: JsonApiDeserialize + Send + Sync + 'static> FromIncluded<T> for Arc<T> {
+    fn from_included(included_map: &mut IncludedMap, reference: &Reference) -> Result<Self, Error> {
+        included_map.get_shared(&reference.kind, &reference.id)
+    }
+}
+
+impl<T: JsonApiDeserialize + 'static> FromIncluded<T> for Rc<T> {
+    fn from_included(included_map: &mut IncludedMap, reference: &Reference) -> Result<Self, Error> {
+        included_map.get_shared(&reference.kind, &reference.id)
+    }
+}
+
+impl<T: JsonApiDeserialize + Clone + 'static> FromIncluded<T> for Box<T> {
+    fn from_included(included_map: &mut IncludedMap, reference: &Reference) -> Result<Self, Error> {
+        let resource: Rc<T> = included_map.get_shared(&reference.kind, &reference.id)?;
+        Ok(Box::new(T::clone(&resource)))
+    }
+}
+
+impl<T: JsonApiDeserialize + Clone + 'static> FromIncluded<T> for T {
+    fn from_included(included_map: &mut IncludedMap, reference: &Reference) -> Result<Self, Error> {
+        let resource: Rc<T> = included_map.get_shared(&reference.kind, &reference.id)?;
+        Ok(T::clone(&resource))
+    }
+}
+
+/// Pairs a resource with its ID, which allows to-many relationships to be collected into maps.
+impl<T, P: FromIncluded<T>> FromIncluded<T> for (String, P) {
+    fn from_included(included_map: &mut IncludedMap, reference: &Reference) -> Result<Self, Error> {
+        Ok((
+            reference.id.clone(),
+            P::from_included(included_map, reference)?,
+        ))
+    }
+}
+
+/// A resolved relationship together with the `meta` of its resource identifier.
+///
+/// Identifier `meta` is deserialized into `M`. When it is absent, `M` is deserialized from `null`
+/// or, failing that, from an empty object, so that `Option` and maps with all-default fields work
+/// as expected.
+#[derive(Debug, Clone)]
+pub struct Linked<P, M = HashMap<String, Value>> {
+    pub resource: P,
+    pub meta: M,
+}
+
+impl<P, M> Deref for Linked<P, M> {
+    type Target = P;
+
+    fn deref(&self) -> &Self::Target {
+        &self.resource
+    }
+}
+
+impl<T, P: FromIncluded<T>, M: DeserializeOwned> FromIncluded<T> for Linked<P, M> {
+    fn from_included(included_map: &mut IncludedMap, reference: &Reference) -> Result<Self, Error> {
+        let meta = match &reference.meta {
+            Some(meta) => M::deserialize(Value::Object(meta.clone().into_iter().collect()))?,
+            None => match M::deserialize(Value::Null) {
+                Ok(meta) => meta,
+                Err(_) => M::deserialize(Value::Object(Map::new()))?,
+            },
+        };
+
+        Ok(Self {
+            resource: P::from_included(included_map, reference)?,
+            meta,
+        })
+    }
+}
+
+/// Resolves included resources and keeps track of the state of the deserialization.
+///
+/// Next to the included resources, this holds the JSON pointer of the value currently being
+/// deserialized and, when errors are accumulated, all errors encountered so far.
 #[derive(Default)]
-pub struct IncludedMap<'a> {
-    raw_map: HashMap<(&'a str, &'a str), &'a RawResource>,
-    deserialized_map: HashMap<(&'a str, &'a str, TypeId), Arc<dyn Any + Send + Sync>>,
+pub struct IncludedMap {
+    resources: Vec<RawResource>,
+    raw_map: HashMap<String, HashMap<String, usize>>,
+    deserialized_map: HashMap<(usize, TypeId), Arc<dyn Any + Send + Sync>>,
+    failed: HashSet<(usize, TypeId)>,
+    resolved: HashSet<usize>,
+    pointer: String,
+    errors: Option<Vec<LocatedError>>,
+    resolvers: Vec<(String, TypeId, Resolver)>,
+    id: MapId,
+    depth: usize,
 }
 
-impl IncludedMap<'_> {
+/// Resolves an included resource by type and ID into a type known from an earlier lookup.
+type Resolver = fn(&mut IncludedMap, &str, &str) -> Result<(), Error>;
+
+impl IncludedMap {
     pub fn get<T>(&mut self, kind: &str, id: &str) -> Result<Arc<T>, Error>
     where
         T: JsonApiDeserialize + Send + Sync + 'static,
     {
-        let type_id = TypeId::of::<T>();
+        self.get_shared(kind, id)
+    }
+
+    /// Returns an included resource behind the shared pointer `P`.
+    ///
+    /// Each resource is deserialized at most once per pointer type, later lookups return a clone
+    /// of the same pointer. Pointers which are not `Send`, like `Rc`, are only shared within a
+    /// single call into the map, such as the `from_value()` of a derived resource, or within
+    /// [`IncludedMap::sharing()`].
+    pub fn get_shared<P, T>(&mut self, kind: &str, id: &str) -> Result<P, Error>
+    where
+        P: SharedPointer<T>,
+        T: JsonApiDeserialize + 'static,
+    {
+        if self.errors.is_some() {
+            self.add_resolver::<T>(kind);
+        }
+
+        let index = *self
+            .raw_map
+            .get(kind)
+            .and_then(|ids| ids.get(id))
+            .ok_or_else(|| Error::MissingResource {
+                kind: kind.to_string(),
+                id: id.to_string(),
+            })?;
+        self.resolved.insert(index);
+
+        if let Some(existing) = P::cached(self, index) {
+            return Ok(existing);
+        }
 
-        if let Some(existing) = self.deserialized_map.get(&(kind, id, type_id)).cloned() {
-            return Ok(existing.downcast_ref::<Handle<T>>().unwrap().0.clone());
+        if self.failed.contains(&(index, TypeId::of::<T>())) {
+            return Err(Error::reported());
         }
 
-        let (kind, id, value) = {
-            let raw_resource =
-                self.raw_map
-                    .get(&(kind, id))
-                    .ok_or_else(|| Error::MissingResource {
-                        kind: kind.to_string(),
-                        id: id.to_string(),
-                    })?;
-            let kind = raw_resource.kind.as_str();
-            let id = raw_resource.id.as_str();
-            let value: Value = (*raw_resource).into();
+        let value: Value = (&self.resources[index]).into();
 
-            (kind, id, value)
+        self.enter();
+        let pointer = std::mem::replace(&mut self.pointer, format!("/included/{}", index));
+        let result = T::from_value(&value, self).map_err(|error| self.record(error));
+        self.pointer = pointer;
+
+        let result = match result {
+            Ok(resource) => {
+                let resource = P::new(resource);
+                resource.cache(self, index);
+                Ok(resource)
+            }
+            Err(error) => {
+                if self.errors.is_some() {
+                    self.failed.insert((index, TypeId::of::<T>()));
+                }
+
+                Err(error)
+            }
         };
+        self.leave();
 
-        let handle = Handle(Arc::new(T::from_value(&value, self)?));
-        let resource = handle.0.clone();
-        self.deserialized_map
-            .insert((kind, id, type_id), Arc::new(handle));
-        Ok(resource)
+        result
     }
-}
 
-impl<'a> From<&'a Vec<RawResource>> for IncludedMap<'a> {
-    fn from(resources: &'a Vec<RawResource>) -> Self {
-        let raw_map = resources
-            .iter()
-            .map(|raw| ((raw.kind.as_str(), raw.id.as_str()), raw))
-            .collect();
+    /// Resolves the resource identifiers of a to-many relationship into any collection.
+    ///
+    /// When errors are accumulated, all identifiers are resolved, and every failure is recorded
+    /// with the pointer of its identifier.
+    pub fn resolve_many<T, P, C>(&mut self, references: &[Reference]) -> Result<C, Error>
+    where
+        P: FromIncluded<T>,
+        C: FromIterator<P>,
+    {
+        let mut items = Vec::with_capacity(references.len());
+        let mut failed = false;
+
+        for (index, reference) in references.iter().enumerate() {
+            match self.scoped(&format!("/data/{}", index), |included_map| {
+                P::from_included(included_map, reference)
+            }) {
+                Ok(item) => items.push(item),
+                Err(error) if error.is_reported() => failed = true,
+                Err(error) => return Err(error),
+            }
+        }
+
+        if failed {
+            return Err(Error::reported());
+        }
+
+        Ok(items.into_iter().collect())
+    }
+
+    /// Runs `f` as a single call into the map, so that pointers which are not `Send`, like `Rc`,
+    /// are shared between all lookups within it.
+    pub fn sharing<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.enter();
+        let result = f(self);
+        self.leave();
+        result
+    }
+
+    /// Returns the JSON pointer of the value currently being deserialized.
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+
+    /// Returns whether errors are accumulated instead of failing on the first one.
+    pub fn is_accumulating(&self) -> bool {
+        self.errors.is_some()
+    }
+
+    /// Deserializes a member of the current value, with the pointer extended by `segment`.
+    ///
+    /// When errors are accumulated, a failure is recorded together with its pointer and a
+    /// placeholder is returned in its place, so that the caller can carry on with the remaining
+    /// members.
+    pub fn scoped<R>(
+        &mut self,
+        segment: &str,
+        f: impl FnOnce(&mut Self) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let length = self.pointer.len();
+        self.pointer.push_str(segment);
+        self.enter();
+        let result = f(self).map_err(|error| self.record(error));
+        self.leave();
+        self.pointer.truncate(length);
+        result
+    }
+
+    /// Creates the map, resolving resources with the same type and ID according to `policy`.
+    pub fn new(mut resources: Vec<RawResource>, policy: DuplicatePolicy) -> Self {
+        let mut raw_map: HashMap<String, HashMap<String, usize>> = HashMap::new();
+
+        for index in 0..resources.len() {
+            let ids = raw_map.entry(resources[index].kind.clone()).or_default();
+
+            match ids.entry(resources[index].id.clone()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(index);
+                }
+                Entry::Occupied(mut entry) => match policy {
+                    DuplicatePolicy::Error | DuplicatePolicy::FirstWins => {}
+                    DuplicatePolicy::LastWins => {
+                        entry.insert(index);
+                    }
+                    DuplicatePolicy::DeepMerge => {
+                        let duplicate = resources[index].clone();
+                        resources[*entry.get()].merge(duplicate);
+                    }
+                },
+            }
+        }
 
         Self {
+            resources,
             raw_map,
-            deserialized_map: HashMap::new(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the included resources which have never been resolved, in document order.
+    pub(crate) fn unused(&self) -> Vec<Reference> {
+        let mut unused: Vec<usize> = self
+            .raw_map
+            .values()
+            .flat_map(|ids| ids.values().copied())
+            .filter(|index| !self.resolved.contains(index))
+            .collect();
+        unused.sort_unstable();
+
+        unused
+            .into_iter()
+            .map(|index| {
+                let resource = &self.resources[index];
+                Reference::new(&resource.kind, &resource.id)
+            })
+            .collect()
+    }
+
+    /// Deserializes the included resources which have never been resolved, so that their errors
+    /// are recorded as well.
+    ///
+    /// A resource can only be deserialized when other resources of its type have been looked up
+    /// before, which tells the Rust types to deserialize it into. All other resources are skipped.
+    pub(crate) fn resolve_remaining(&mut self) {
+        self.enter();
+
+        while let Some((kind, id, resolvers)) = self.next_unresolved() {
+            for resolve in resolvers {
+                // Failures have been recorded already.
+                let _ = resolve(self, &kind, &id);
+            }
         }
+
+        self.leave();
+    }
+
+    /// Returns the first included resource which has never been resolved, but can be.
+    fn next_unresolved(&self) -> Option<(String, String, Vec<Resolver>)> {
+        let mut unresolved: Vec<usize> = self
+            .raw_map
+            .values()
+            .flat_map(|ids| ids.values().copied())
+            .filter(|index| !self.resolved.contains(index))
+            .collect();
+        unresolved.sort_unstable();
+
+        unresolved.into_iter().find_map(|index| {
+            let resource = &self.resources[index];
+            let resolvers: Vec<Resolver> = self
+                .resolvers
+                .iter()
+                .filter(|(kind, _, _)| *kind == resource.kind)
+                .map(|(_, _, resolver)| *resolver)
+                .collect();
+
+            (!resolvers.is_empty()).then(|| (resource.kind.clone(), resource.id.clone(), resolvers))
+        })
+    }
+
+    /// Remembers that resources of type `kind` are deserialized into `T`.
+    fn add_resolver<T: JsonApiDeserialize + 'static>(&mut self, kind: &str) {
+        let known = self
+            .resolvers
+            .iter()
+            .any(|(known, type_id, _)| known == kind && *type_id == TypeId::of::<T>());
+
+        if !known {
+            self.resolvers.push((
+                kind.to_string(),
+                TypeId::of::<T>(),
+                |included_map, kind, id| included_map.get_shared::<Rc<T>, T>(kind, id).map(drop),
+            ));
+        }
+    }
+
+    pub(crate) fn without_accumulation<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let errors = self.errors.take();
+        let result = f(self);
+        self.errors = errors;
+        result
+    }
+
+    pub(crate) fn accumulate_errors(&mut self) {
+        self.errors = Some(Vec::new());
+    }
+
+    pub(crate) fn take_errors(&mut self) -> Vec<LocatedError> {
+        self.errors.take().unwrap_or_default()
+    }
+
+    fn enter(&mut self) {
+        self.depth += 1;
+    }
+
+    /// Leaves a call into the map, dropping the thread-local resources once the outermost call
+    /// returns.
+    fn leave(&mut self) {
+        self.depth -= 1;
+
+        if self.depth == 0 {
+            self.id.clear_local_cache();
+        }
+    }
+
+    fn record(&mut self, error: Error) -> Error {
+        match (&mut self.errors, error) {
+            (_, error) if error.is_reported() => error,
+            (Some(errors), error) => {
+                errors.push(LocatedError {
+                    pointer: self.pointer.clone(),
+                    error,
+                });
+                Error::reported()
+            }
+            (None, error) => error,
+        }
+    }
+}
+
+/// Creates the map from the `included` member of a document.
+impl TryFrom<Value> for IncludedMap {
+    type Error = Error;
+
+    fn try_from(included: Value) -> Result<Self, Self::Error> {
+        let resources: Vec<RawResource> = serde_json::from_value(included)?;
+        Ok(resources.into())
+    }
+}
+
+impl From<Vec<RawResource>> for IncludedMap {
+    fn from(resources: Vec<RawResource>) -> Self {
+        Self::new(resources, DuplicatePolicy::LastWins)
     }
 }
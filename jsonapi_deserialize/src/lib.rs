@@ -3,15 +3,40 @@ mod document;
 mod error;
 mod included;
 mod link;
+mod query;
+mod resource;
+mod response;
+pub mod serde_document;
+mod stream;
+mod validate;
 
-pub use deserialize::{deserialize_document, Error as DeserializeError, JsonApiDeserialize};
+pub use deserialize::{
+    deserialize_document, deserialize_document_partial, deserialize_document_value,
+    deserialize_document_value_with_options, deserialize_document_with_options,
+    deserialize_meta_document, DeserializeOptions, DuplicatePolicy, Error as DeserializeError,
+    JsonApiDeserialize, LocatedError, Warning,
+};
 pub use document::{
-    Document, DocumentError, DocumentLinks, ErrorLinks, ErrorSource, RawMultipleRelationship,
-    RawOptionalRelationship, RawSingleRelationship, Reference,
+    Document, DocumentError, DocumentLinks, ErrorLinks, ErrorSource, RawDocument,
+    RawMultipleRelationship, RawOptionalRelationship, RawResource, RawSingleRelationship,
+    Reference, RelationshipLinks, ResourceLinks,
 };
 pub use error::Error;
-pub use included::IncludedMap;
-pub use link::Link;
+pub use included::{FromIncluded, IncludedMap, Linked, SharedPointer};
+pub use link::{Link, UriReference};
+pub use query::QueryBuilder;
+pub use resource::{
+    AttributeDescriptor, Cardinality, JsonApiResource, RelationshipDescriptor, ResourceDescriptor,
+};
+pub use response::{
+    deserialize_response, deserialize_response_with_options, Error as ResponseError,
+    ResponseDocument, ResponseOptions,
+};
+pub use serde_document::JsonApiDocument;
+pub use stream::{deserialize_document_iter, DocumentIter};
+#[cfg(feature = "stream")]
+pub use stream::{deserialize_document_stream, DocumentStream};
+pub use validate::{validate_document, Rule, SpecViolation};
 
 extern crate jsonapi_deserialize_derive;
 pub use jsonapi_deserialize_derive::JsonApiDeserialize;
@@ -1,45 +1,151 @@
-use http::{uri, Uri};
+use http::Uri;
 use langtag::LangTagBuf;
+use mime::Mime;
 use serde::de::{self, MapAccess, Unexpected, Visitor};
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::fmt::Formatter;
+use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
 use thiserror::Error;
+use url::{ParseError, Url};
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Invalid link URI")]
-    UriError(#[from] uri::InvalidUri),
+    UriError(String),
+}
+
+/// A URI reference as described in RFC 3986 section 4.1, which is either an absolute URI or a
+/// reference relative to a base URI, such as `../articles` or `?page=2`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UriReference(String);
+
+impl UriReference {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the reference is relative, i.e. it has no scheme.
+    pub fn is_relative(&self) -> bool {
+        matches!(Url::parse(&self.0), Err(ParseError::RelativeUrlWithoutBase))
+    }
+
+    /// Converts the reference into a `Uri`, which fails for some relative references and drops
+    /// the fragment.
+    pub fn to_uri(&self) -> Option<Uri> {
+        Uri::from_str(&self.0).ok()
+    }
+
+    /// Resolves the reference against a base URI, following RFC 3986 section 5.2.
+    ///
+    /// Fails when the base URI is relative, or when the target can't be represented as a `Uri`.
+    /// The fragment of the target is dropped.
+    pub fn resolve(&self, base: &Uri) -> Result<Uri, Error> {
+        let target = join(base, &self.0)?;
+        Uri::from_str(target.as_str()).map_err(|_| Error::UriError(target.into()))
+    }
+}
+
+impl FromStr for UriReference {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Anything `Uri` accepts is kept valid, next to the relative references it can't parse.
+        let base = Url::parse("http://localhost/").expect("base URL is valid");
+
+        if Uri::from_str(s).is_err() && base.join(s).is_err() {
+            return Err(Error::UriError(s.to_string()));
+        }
+
+        Ok(UriReference(s.to_string()))
+    }
+}
+
+impl Display for UriReference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialEq<str> for UriReference {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for UriReference {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Uri> for UriReference {
+    fn eq(&self, other: &Uri) -> bool {
+        self.to_uri().as_ref() == Some(other)
+    }
+}
+
+impl<'de> Deserialize<'de> for UriReference {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw: String = Deserialize::deserialize(deserializer)?;
+        UriReference::from_str(&raw)
+            .map_err(|_| de::Error::invalid_value(Unexpected::Str(&raw), &"URI reference"))
+    }
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(remote = "Self")]
 pub struct Link {
-    #[serde(deserialize_with = "deserialize_uri")]
-    pub href: Uri,
+    pub href: UriReference,
     pub rel: Option<String>,
-    #[serde(rename = "describedby")]
-    pub described_by: Option<String>,
+    pub describedby: Option<Box<Link>>,
     pub title: Option<String>,
-    #[serde(rename = "type")]
-    pub media_type: Option<String>,
-    pub href_lang: Option<LangTagBuf>,
+    #[serde(rename = "type", default, deserialize_with = "deserialize_media_type")]
+    pub media_type: Option<Mime>,
+    #[serde(default, deserialize_with = "deserialize_lang_tags")]
+    pub hreflang: Vec<LangTagBuf>,
     pub meta: Option<HashMap<String, Value>>,
 }
 
+impl Link {
+    /// Resolves the `href` of this link against a base URI, following RFC 3986 section 5.2.
+    ///
+    /// Fails when the base URI is relative, or when the target can't be represented as a `Uri`.
+    pub fn resolve(&self, base: &Uri) -> Result<Uri, Error> {
+        self.href.resolve(base)
+    }
+
+    /// Returns this link with its `href` resolved against a base URI.
+    ///
+    /// Unlike [`Link::resolve()`], this keeps the fragment, and leaves the `href` untouched when
+    /// it can't be resolved.
+    pub fn with_base_uri(mut self, base: &Uri) -> Self {
+        if let Ok(target) = join(base, self.href.as_str()) {
+            self.href = UriReference(target.into());
+        }
+
+        self.describedby = self
+            .describedby
+            .map(|link| Box::new(link.with_base_uri(base)));
+        self
+    }
+}
+
 impl FromStr for Link {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Link {
-            href: Uri::from_str(s)?,
+            href: UriReference::from_str(s)?,
             rel: None,
-            described_by: None,
+            describedby: None,
             title: None,
             media_type: None,
-            href_lang: None,
+            hreflang: Vec::new(),
             meta: None,
         })
     }
@@ -80,16 +186,83 @@ impl<'de> Deserialize<'de> for Link {
     }
 }
 
-fn deserialize_uri<'de, D>(deserializer: D) -> Result<Uri, D::Error>
+fn deserialize_media_type<'de, D>(deserializer: D) -> Result<Option<Mime>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let raw: String = Deserialize::deserialize(deserializer)?;
-    Uri::from_str(&raw).map_err(|error| {
-        de::Error::invalid_value(Unexpected::Str(&error.to_string()), &"URI reference")
+    let raw: Option<String> = Deserialize::deserialize(deserializer)?;
+
+    raw.map(|raw| {
+        Mime::from_str(&raw).map_err(|error| {
+            de::Error::invalid_value(Unexpected::Str(&error.to_string()), &"media type")
+        })
+    })
+    .transpose()
+}
+
+fn deserialize_lang_tags<'de, D>(deserializer: D) -> Result<Vec<LangTagBuf>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum LangTags {
+        Single(LangTagBuf),
+        Multiple(Vec<LangTagBuf>),
+    }
+
+    Ok(match Option::<LangTags>::deserialize(deserializer)? {
+        Some(LangTags::Single(tag)) => vec![tag],
+        Some(LangTags::Multiple(tags)) => tags,
+        None => Vec::new(),
     })
 }
 
+/// Resolves the `href` of every link within a raw links object against a base URI, leaving
+/// anything which is not a valid link untouched.
+pub(crate) fn resolve_link_values(links: &mut Value, base: &Uri) {
+    let Value::Object(links) = links else {
+        return;
+    };
+
+    for link in links.values_mut() {
+        resolve_link_value(link, base);
+    }
+}
+
+fn resolve_link_value(link: &mut Value, base: &Uri) {
+    match link {
+        Value::String(href) => {
+            if let Ok(target) = join(base, href) {
+                *href = target.into();
+            }
+        }
+        Value::Object(object) => {
+            if let Some(href) = object.get_mut("href") {
+                resolve_link_value(href, base);
+            }
+
+            if let Some(described_by) = object.get_mut("describedby") {
+                resolve_link_value(described_by, base);
+            }
+        }
+        Value::Array(links) => {
+            for link in links {
+                resolve_link_value(link, base);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Resolves a reference against a base URI, which `url` does according to RFC 3986 for all
+/// references of hierarchical URIs.
+fn join(base: &Uri, reference: &str) -> Result<Url, Error> {
+    Url::parse(&base.to_string())
+        .and_then(|base| base.join(reference))
+        .map_err(|_| Error::UriError(reference.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,4 +290,177 @@ mod tests {
         let result: Result<Link, _> = serde_json::from_str(r#"{}"#);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_deserialize_full_object() {
+        let link: Link = serde_json::from_str(
+            r#"{
+                "href": "http://example.com/articles",
+                "rel": "alternate",
+                "describedby": { "href": "http://example.com/schemas/articles" },
+                "title": "Articles",
+                "type": "application/vnd.api+json",
+                "hreflang": "en-US",
+                "meta": { "count": 10 }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(link.rel.as_deref(), Some("alternate"));
+        assert_eq!(
+            link.describedby.unwrap().href,
+            Uri::from_str("http://example.com/schemas/articles").unwrap()
+        );
+        assert_eq!(link.title.as_deref(), Some("Articles"));
+        assert_eq!(
+            link.media_type.unwrap().essence_str(),
+            "application/vnd.api+json"
+        );
+        assert_eq!(link.hreflang.len(), 1);
+        assert_eq!(link.hreflang[0].as_str(), "en-US");
+        assert_eq!(link.meta.unwrap()["count"], 10);
+    }
+
+    #[test]
+    fn test_deserialize_hreflang_array() {
+        let link: Link =
+            serde_json::from_str(r#"{"href": "/example", "hreflang": ["en", "de-DE"]}"#).unwrap();
+        let tags: Vec<_> = link.hreflang.iter().map(|tag| tag.as_str()).collect();
+        assert_eq!(tags, ["en", "de-DE"]);
+    }
+
+    #[test]
+    fn test_deserialize_null_members() {
+        let link: Link = serde_json::from_str(
+            r#"{"href": "/example", "describedby": null, "type": null, "hreflang": null}"#,
+        )
+        .unwrap();
+        assert!(link.describedby.is_none());
+        assert!(link.media_type.is_none());
+        assert!(link.hreflang.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_media_type() {
+        let result: Result<Link, _> = serde_json::from_str(r#"{"href": "/example", "type": "x"}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_deserialize_null_link() {
+        let link: Option<Link> = serde_json::from_str("null").unwrap();
+        assert!(link.is_none());
+    }
+
+    #[test]
+    fn test_resolve_relative_href() {
+        let link: Link = serde_json::from_str(r#""/articles?page=2""#).unwrap();
+        let base = Uri::from_str("https://example.com/api/articles/1").unwrap();
+        assert_eq!(
+            link.resolve(&base).unwrap(),
+            Uri::from_str("https://example.com/articles?page=2").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_resolve_absolute_href() {
+        let link: Link = serde_json::from_str(r#""http://example.com/a/../b""#).unwrap();
+        let base = Uri::from_str("https://example.org/").unwrap();
+        assert_eq!(
+            link.resolve(&base).unwrap(),
+            Uri::from_str("http://example.com/b").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_deserialize_relative_references() {
+        for reference in ["a/b", "../x", "?page=2", "#frag", ""] {
+            let link: Link = serde_json::from_value(Value::String(reference.to_string())).unwrap();
+            assert_eq!(link.href, reference);
+            assert!(link.href.is_relative());
+        }
+    }
+
+    #[test]
+    fn test_deserialize_lenient_href() {
+        for href in ["http://x/a?filter=a|b", "?q={x}", "/articles/\u{e9}", "a b"] {
+            let link: Link = serde_json::from_value(Value::String(href.to_string())).unwrap();
+            assert_eq!(link.href, href);
+        }
+    }
+
+    #[test]
+    fn test_invalid_href() {
+        for href in ["http://exa mple.com/", "http://[::1"] {
+            let result: Result<Link, _> = serde_json::from_value(Value::String(href.to_string()));
+            assert!(result.is_err(), "accepted {:?}", href);
+        }
+    }
+
+    #[test]
+    fn test_resolve_with_relative_base() {
+        let link: Link = serde_json::from_str(r#""comments""#).unwrap();
+        let base = Uri::from_str("/articles/1").unwrap();
+        assert!(link.resolve(&base).is_err());
+    }
+
+    #[test]
+    fn test_with_base_uri_keeps_fragment() {
+        let link: Link = serde_json::from_str(r#""../comments#latest""#).unwrap();
+        let base = Uri::from_str("https://example.com/articles/1").unwrap();
+        assert_eq!(
+            link.with_base_uri(&base).href,
+            "https://example.com/comments#latest"
+        );
+    }
+
+    #[test]
+    fn test_resolve_reference_examples() {
+        // Normal and abnormal examples from RFC 3986 section 5.4.
+        let base = Uri::from_str("http://a/b/c/d;p?q").unwrap();
+        let examples = [
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("g;x", "http://a/b/c/g;x"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+            ("g?y/./x", "http://a/b/c/g?y/./x"),
+            ("g#s/../x", "http://a/b/c/g#s/../x"),
+        ];
+
+        for (reference, expected) in examples {
+            let link: Link = serde_json::from_value(Value::String(reference.to_string())).unwrap();
+            assert_eq!(
+                link.resolve(&base).unwrap(),
+                Uri::from_str(expected).unwrap(),
+                "resolving {:?}",
+                reference
+            );
+        }
+    }
 }
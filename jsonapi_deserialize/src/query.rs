@@ -0,0 +1,169 @@
+use crate::resource::{JsonApiResource, ResourceDescriptor};
+use std::fmt::{Display, Formatter};
+use url::form_urlencoded;
+
+/// Builds the query parameters of a request for a specific resource type.
+///
+/// The `include` and `fields[...]` parameters are derived from the relationships and attributes
+/// of the resource and all resources reachable through it, so that the response contains exactly
+/// what is required to deserialize it.
+#[derive(Debug, Clone)]
+pub struct QueryBuilder {
+    include: Vec<String>,
+    fields: Vec<(&'static str, Vec<&'static str>)>,
+    sort: Vec<String>,
+    filter: Vec<(String, String)>,
+    page: Vec<(String, String)>,
+}
+
+impl QueryBuilder {
+    pub fn for_resource<T: JsonApiResource>() -> Self {
+        let mut builder = Self {
+            include: Vec::new(),
+            fields: Vec::new(),
+            sort: Vec::new(),
+            filter: Vec::new(),
+            page: Vec::new(),
+        };
+
+        builder.walk(T::descriptor(), &mut Vec::new(), &mut Vec::new());
+        builder
+    }
+
+    /// Adds a sort field, which may be prefixed with `-` for descending order.
+    pub fn sort(mut self, field: impl Into<String>) -> Self {
+        self.sort.push(field.into());
+        self
+    }
+
+    /// Adds a `filter[key]` parameter.
+    pub fn filter(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.filter.push((key.into(), value.into()));
+        self
+    }
+
+    /// Adds a `page[key]` parameter.
+    pub fn page(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.page.push((key.into(), value.into()));
+        self
+    }
+
+    /// Returns the include paths, in the order they were discovered.
+    pub fn include_paths(&self) -> &[String] {
+        &self.include
+    }
+
+    /// Returns the unencoded query parameters.
+    pub fn pairs(&self) -> Vec<(String, String)> {
+        let mut pairs = Vec::new();
+
+        if !self.include.is_empty() {
+            pairs.push(("include".to_string(), self.include.join(",")));
+        }
+
+        for (kind, fields) in &self.fields {
+            pairs.push((format!("fields[{}]", kind), fields.join(",")));
+        }
+
+        if !self.sort.is_empty() {
+            pairs.push(("sort".to_string(), self.sort.join(",")));
+        }
+
+        for (key, value) in &self.filter {
+            pairs.push((format!("filter[{}]", key), value.clone()));
+        }
+
+        for (key, value) in &self.page {
+            pairs.push((format!("page[{}]", key), value.clone()));
+        }
+
+        pairs
+    }
+
+    /// Returns the URL encoded query string, without a leading `?`.
+    pub fn build(&self) -> String {
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.pairs())
+            .finish()
+    }
+
+    fn walk(
+        &mut self,
+        descriptor: &'static ResourceDescriptor,
+        path: &mut Vec<&'static str>,
+        visited: &mut Vec<*const ResourceDescriptor>,
+    ) {
+        visited.push(descriptor);
+        self.add_fields(descriptor);
+
+        let mut is_leaf = true;
+
+        for relationship in descriptor.relationships {
+            let Some(resource) = relationship.resource else {
+                continue;
+            };
+
+            let related = resource();
+
+            path.push(relationship.name);
+
+            if visited.contains(&(related as *const _)) {
+                // The related type is already being walked, so only the relationship itself is
+                // included to avoid cycles.
+                self.add_fields(related);
+                self.include.push(path.join("."));
+            } else {
+                self.walk(related, path, visited);
+            }
+
+            path.pop();
+            is_leaf = false;
+        }
+
+        // Intermediate resources are always returned by the server, so only leaf paths are
+        // required.
+        if is_leaf && !path.is_empty() {
+            self.include.push(path.join("."));
+        }
+
+        visited.pop();
+    }
+
+    fn add_fields(&mut self, descriptor: &'static ResourceDescriptor) {
+        let index = match self
+            .fields
+            .iter()
+            .position(|(kind, _)| *kind == descriptor.kind)
+        {
+            Some(index) => index,
+            None => {
+                self.fields.push((descriptor.kind, Vec::new()));
+                self.fields.len() - 1
+            }
+        };
+
+        let fields = &mut self.fields[index].1;
+
+        for name in descriptor
+            .attributes
+            .iter()
+            .map(|attribute| attribute.name)
+            .chain(
+                descriptor
+                    .relationships
+                    .iter()
+                    .map(|relationship| relationship.name),
+            )
+        {
+            if !fields.contains(&name) {
+                fields.push(name);
+            }
+        }
+    }
+}
+
+impl Display for QueryBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.build())
+    }
+}
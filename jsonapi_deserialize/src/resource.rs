@@ -0,0 +1,104 @@
+use crate::deserialize::JsonApiDeserialize;
+
+/// Static metadata about a resource type, usually implemented through the derive macro.
+///
+/// This allows tooling like query builders, mock generators or error mappers to reflect on the
+/// shape of a resource at runtime.
+pub trait JsonApiResource: JsonApiDeserialize {
+    /// The resource type as it appears in the `type` member of a resource object.
+    const TYPE: &'static str;
+
+    /// Returns the descriptor of the resource's attributes and relationships.
+    fn descriptor() -> &'static ResourceDescriptor;
+}
+
+#[derive(Debug)]
+pub struct ResourceDescriptor {
+    pub kind: &'static str,
+    pub attributes: &'static [AttributeDescriptor],
+    pub relationships: &'static [RelationshipDescriptor],
+}
+
+impl ResourceDescriptor {
+    /// Returns the attribute with the given member name.
+    pub fn attribute(&self, name: &str) -> Option<&'static AttributeDescriptor> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
+
+    /// Returns the relationship with the given member name.
+    pub fn relationship(&self, name: &str) -> Option<&'static RelationshipDescriptor> {
+        self.relationships
+            .iter()
+            .find(|relationship| relationship.name == name)
+    }
+
+    /// Returns the JSON pointer of a struct field, relative to the resource object.
+    pub fn field_pointer(&self, field: &str) -> Option<String> {
+        if let Some(attribute) = self
+            .attributes
+            .iter()
+            .find(|attribute| attribute.field == field)
+        {
+            return Some(format!("/attributes/{}", attribute.name));
+        }
+
+        self.relationships
+            .iter()
+            .find(|relationship| relationship.field == field)
+            .map(|relationship| format!("/relationships/{}", relationship.name))
+    }
+
+    /// Returns the struct field a JSON pointer relative to the resource object refers to.
+    ///
+    /// Pointers into a member, like `/attributes/address/city`, map to the field of the member.
+    pub fn pointer_field(&self, pointer: &str) -> Option<&'static str> {
+        let mut segments = pointer.strip_prefix('/')?.split('/');
+
+        match (segments.next()?, segments.next()?) {
+            ("attributes", name) => self.attribute(name).map(|attribute| attribute.field),
+            ("relationships", name) => self
+                .relationship(name)
+                .map(|relationship| relationship.field),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AttributeDescriptor {
+    /// The name of the struct field.
+    pub field: &'static str,
+    /// The member name within the `attributes` object.
+    pub name: &'static str,
+    /// Whether the field is filled with its default value when the member is missing.
+    pub default: bool,
+    /// Whether the field is `None` when the member is missing.
+    pub optional: bool,
+}
+
+#[derive(Debug)]
+pub struct RelationshipDescriptor {
+    /// The name of the struct field.
+    pub field: &'static str,
+    /// The member name within the `relationships` object.
+    pub name: &'static str,
+    pub cardinality: Cardinality,
+    /// The descriptor of the related resource, if it is resolved from the included resources.
+    pub resource: Option<fn() -> &'static ResourceDescriptor>,
+    /// Whether the field is filled with its default value when the member is missing.
+    pub default: bool,
+    /// Whether the field is `None` when the member is missing.
+    pub optional: bool,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Cardinality {
+    /// A to-one relationship which is never `null`.
+    Single,
+    /// A to-one relationship which may be `null`.
+    Optional,
+    /// A to-many relationship.
+    Multiple,
+}
@@ -0,0 +1,181 @@
+use crate::deserialize::{
+    deserialize_document_value_with_options, deserialize_raw_document, DeserializeOptions,
+    Error as DeserializeError, JsonApiDeserialize,
+};
+use crate::document::{Document, RawDocument};
+use http::{header, HeaderMap, Response, StatusCode, Uri};
+use mime::Mime;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::str::FromStr;
+use thiserror::Error;
+
+const MEDIA_TYPE: &str = "application/vnd.api+json";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The `Content-Type` of the response is missing, not the JSON:API media type or has
+    /// parameters other than `ext` and `profile`.
+    #[error("Unsupported media type")]
+    UnsupportedMediaType(Option<String>),
+
+    /// The media type of the response applies extensions which the client does not support.
+    #[error("Not acceptable")]
+    NotAcceptable { extensions: Vec<String> },
+
+    /// The response to a conditional request has no document, as the one the client already has
+    /// is still current.
+    #[error("Not modified")]
+    NotModified { etag: Option<String> },
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ResponseOptions {
+    /// How to deserialize the document within the response.
+    pub deserialize: DeserializeOptions,
+    /// The URIs of the extensions the client supports. Responses applying any other extension
+    /// are rejected as not acceptable.
+    pub extensions: Vec<String>,
+}
+
+/// A document deserialized from a successful response, together with its relevant headers.
+#[derive(Debug)]
+pub struct ResponseDocument<T: JsonApiDeserialize, M = HashMap<String, Value>> {
+    pub status: StatusCode,
+    /// The `Location` header, e.g. of a created resource.
+    pub location: Option<Uri>,
+    /// The `ETag` header.
+    pub etag: Option<String>,
+    pub document: Document<T, M>,
+}
+
+/// Deserializes the document within the body of an HTTP response.
+///
+/// Document errors are returned as [`crate::Error::DocumentError`] together with the status of
+/// the response, which is also the case for unsuccessful responses without a document. A
+/// `304 Not Modified` response is returned as [`Error::NotModified`] instead.
+pub fn deserialize_response<T: JsonApiDeserialize, B: AsRef<[u8]>>(
+    response: Response<B>,
+) -> Result<ResponseDocument<T>, crate::error::Error> {
+    deserialize_response_with_options(response, &ResponseOptions::default())
+}
+
+/// Deserializes the document within the body of an HTTP response according to the given options.
+///
+/// The top-level `meta` is deserialized into `M`, which is inferred from the returned document.
+pub fn deserialize_response_with_options<T, B, M>(
+    response: Response<B>,
+    options: &ResponseOptions,
+) -> Result<ResponseDocument<T, M>, crate::error::Error>
+where
+    T: JsonApiDeserialize,
+    B: AsRef<[u8]>,
+    M: DeserializeOwned,
+{
+    let (parts, body) = response.into_parts();
+    let body = body.as_ref();
+    let etag = parts
+        .headers
+        .get(header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    if parts.status == StatusCode::NOT_MODIFIED {
+        return Err(Error::NotModified { etag }.into());
+    }
+
+    if !parts.status.is_success() {
+        // Error responses are not required to carry a document, e.g. when they are generated by a
+        // proxy, so the status is reported either way.
+        let errors = check_media_type(&parts.headers, &options.extensions)
+            .ok()
+            .and_then(|()| serde_json::from_slice::<RawDocument>(body).ok())
+            .and_then(|document| document.errors)
+            .unwrap_or_default();
+        let error = crate::error::Error::DocumentError(errors, Some(parts.status));
+
+        return Err(match &options.deserialize.base_uri {
+            Some(base) => error.with_base_uri(base),
+            None => error,
+        });
+    }
+
+    // A response without content is treated as having `null` as primary data.
+    let result = if parts.status == StatusCode::NO_CONTENT {
+        deserialize_raw_document(
+            RawDocument {
+                data: Some(Value::Null),
+                errors: None,
+                meta: None,
+                links: None,
+                included: None,
+            },
+            &options.deserialize,
+        )
+    } else {
+        check_media_type(&parts.headers, &options.extensions)?;
+        let value: Value = serde_json::from_slice(body).map_err(DeserializeError::SerdeError)?;
+        deserialize_document_value_with_options(value, &options.deserialize)
+    };
+
+    let document = match result {
+        Err(crate::error::Error::DocumentError(errors, None)) => {
+            return Err(crate::error::Error::DocumentError(
+                errors,
+                Some(parts.status),
+            ))
+        }
+        result => result?,
+    };
+
+    Ok(ResponseDocument {
+        status: parts.status,
+        location: parts
+            .headers
+            .get(header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| Uri::from_str(value).ok()),
+        etag,
+        document,
+    })
+}
+
+fn check_media_type(headers: &HeaderMap, supported: &[String]) -> Result<(), Error> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned());
+    let unsupported = || Error::UnsupportedMediaType(content_type.clone());
+
+    let media_type = content_type
+        .as_deref()
+        .and_then(|content_type| Mime::from_str(content_type).ok())
+        .ok_or_else(unsupported)?;
+
+    if media_type.essence_str() != MEDIA_TYPE {
+        return Err(unsupported());
+    }
+
+    let mut extensions = Vec::new();
+
+    for (name, value) in media_type.params() {
+        if name == "ext" {
+            extensions.extend(
+                value
+                    .as_str()
+                    .trim_matches('"')
+                    .split_whitespace()
+                    .filter(|extension| !supported.iter().any(|ext| ext == extension))
+                    .map(str::to_string),
+            );
+        } else if name != "profile" {
+            return Err(unsupported());
+        }
+    }
+
+    if !extensions.is_empty() {
+        return Err(Error::NotAcceptable { extensions });
+    }
+
+    Ok(())
+}
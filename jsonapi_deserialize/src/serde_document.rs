@@ -0,0 +1,65 @@
+//! Serde adapter for documents embedded in other payloads.
+//!
+//! ```ignore
+//! #[derive(Deserialize)]
+//! struct Webhook {
+//!     event: String,
+//!     #[serde(with = "jsonapi_deserialize::serde_document")]
+//!     payload: Document<Article>,
+//! }
+//! ```
+
+use crate::deserialize::{
+    deserialize_document_value_with_options, DeserializeOptions, JsonApiDeserialize,
+};
+use crate::document::Document;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+
+/// Deserializes an embedded document, including the resolution of included resources.
+pub fn deserialize<'de, D, T, M>(deserializer: D) -> Result<Document<T, M>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: JsonApiDeserialize,
+    M: DeserializeOwned,
+{
+    let value = Value::deserialize(deserializer)?;
+    deserialize_document_value_with_options(value, &DeserializeOptions::default())
+        .map_err(D::Error::custom)
+}
+
+/// A document which can be embedded in any type implementing [`Deserialize`].
+#[derive(Debug)]
+pub struct JsonApiDocument<T: JsonApiDeserialize, M = HashMap<String, Value>>(pub Document<T, M>);
+
+impl<T: JsonApiDeserialize, M> JsonApiDocument<T, M> {
+    pub fn into_inner(self) -> Document<T, M> {
+        self.0
+    }
+}
+
+impl<T: JsonApiDeserialize, M> Deref for JsonApiDocument<T, M> {
+    type Target = Document<T, M>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: JsonApiDeserialize, M> DerefMut for JsonApiDocument<T, M> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'de, T: JsonApiDeserialize, M: DeserializeOwned> Deserialize<'de> for JsonApiDocument<T, M> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize(deserializer).map(JsonApiDocument)
+    }
+}
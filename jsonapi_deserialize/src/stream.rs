@@ -0,0 +1,646 @@
+use crate::deserialize::{Error, JsonApiDeserialize};
+use crate::document::{DocumentError, DocumentLinks, RawResource};
+use crate::included::IncludedMap;
+use serde::de::Error as _;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::io::Read;
+use std::marker::PhantomData;
+
+const CHUNK_SIZE: usize = 8192;
+
+/// Deserializes the primary resources of a collection document incrementally from a reader.
+///
+/// Only the `data` item currently being deserialized is kept in memory, as long as `included`
+/// precedes `data` in the document or the resources do not reference any included resources.
+/// Otherwise the remaining items are buffered until the included resources have been read.
+///
+/// The top-level `meta` and `links` are available through the iterator once they have been read,
+/// which is guaranteed after the iterator is exhausted.
+pub fn deserialize_document_iter<T, R>(reader: R) -> DocumentIter<T, R>
+where
+    T: JsonApiDeserialize,
+    R: Read,
+{
+    DocumentIter {
+        reader,
+        state: StreamState::default(),
+        chunk: vec![0; CHUNK_SIZE].into_boxed_slice(),
+    }
+}
+
+pub struct DocumentIter<T, R> {
+    reader: R,
+    state: StreamState<T>,
+    chunk: Box<[u8]>,
+}
+
+impl<T, R> DocumentIter<T, R> {
+    pub fn meta(&self) -> Option<&HashMap<String, Value>> {
+        self.state.meta.as_ref()
+    }
+
+    pub fn links(&self) -> Option<&DocumentLinks> {
+        self.state.links.as_ref()
+    }
+}
+
+impl<T, R> Iterator for DocumentIter<T, R>
+where
+    T: JsonApiDeserialize,
+    R: Read,
+{
+    type Item = Result<T, crate::error::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.state.next_step() {
+                Step::Item(item) => return Some(item),
+                Step::Done => return None,
+                Step::NeedData => match self.reader.read(&mut self.chunk) {
+                    Ok(0) => self.state.finish(),
+                    Ok(length) => self.state.feed(&self.chunk[..length]),
+                    Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                    Err(error) => {
+                        self.state.abort();
+                        return Some(Err(error.into()));
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[cfg(feature = "stream")]
+pub use self::r#async::{deserialize_document_stream, DocumentStream};
+
+#[cfg(feature = "stream")]
+mod r#async {
+    use super::{Step, StreamState, CHUNK_SIZE};
+    use crate::deserialize::JsonApiDeserialize;
+    use crate::document::DocumentLinks;
+    use futures_core::Stream;
+    use futures_io::AsyncRead;
+    use serde_json::Value;
+    use std::collections::HashMap;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    /// Deserializes the primary resources of a collection document incrementally from an async
+    /// reader.
+    ///
+    /// This behaves exactly like [`deserialize_document_iter`](super::deserialize_document_iter).
+    pub fn deserialize_document_stream<T, R>(reader: R) -> DocumentStream<T, R>
+    where
+        T: JsonApiDeserialize,
+        R: AsyncRead + Unpin,
+    {
+        DocumentStream {
+            reader,
+            state: StreamState::default(),
+            chunk: vec![0; CHUNK_SIZE].into_boxed_slice(),
+        }
+    }
+
+    pub struct DocumentStream<T, R> {
+        reader: R,
+        state: StreamState<T>,
+        chunk: Box<[u8]>,
+    }
+
+    impl<T, R> DocumentStream<T, R> {
+        pub fn meta(&self) -> Option<&HashMap<String, Value>> {
+            self.state.meta.as_ref()
+        }
+
+        pub fn links(&self) -> Option<&DocumentLinks> {
+            self.state.links.as_ref()
+        }
+    }
+
+    impl<T, R> Stream for DocumentStream<T, R>
+    where
+        T: JsonApiDeserialize,
+        R: AsyncRead + Unpin,
+    {
+        type Item = Result<T, crate::error::Error>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let this = self.get_mut();
+
+            loop {
+                match this.state.next_step() {
+                    Step::Item(item) => return Poll::Ready(Some(item)),
+                    Step::Done => return Poll::Ready(None),
+                    Step::NeedData => {
+                        match Pin::new(&mut this.reader).poll_read(cx, &mut this.chunk) {
+                            Poll::Pending => return Poll::Pending,
+                            Poll::Ready(Ok(0)) => this.state.finish(),
+                            Poll::Ready(Ok(length)) => this.state.feed(&this.chunk[..length]),
+                            Poll::Ready(Err(error))
+                                if error.kind() == std::io::ErrorKind::Interrupted => {}
+                            Poll::Ready(Err(error)) => {
+                                this.state.abort();
+                                return Poll::Ready(Some(Err(error.into())));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+enum Step<T> {
+    Item(Result<T, crate::error::Error>),
+    NeedData,
+    Done,
+}
+
+/// The I/O independent part of the streaming deserializer.
+struct StreamState<T> {
+    scanner: Scanner,
+    included_map: IncludedMap,
+    included_seen: bool,
+    data_seen: bool,
+    pending: VecDeque<(String, Value)>,
+    index: usize,
+    meta: Option<HashMap<String, Value>>,
+    links: Option<DocumentLinks>,
+    finished: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for StreamState<T> {
+    fn default() -> Self {
+        Self {
+            scanner: Scanner::default(),
+            included_map: IncludedMap::default(),
+            included_seen: false,
+            data_seen: false,
+            pending: VecDeque::new(),
+            index: 0,
+            meta: None,
+            links: None,
+            finished: false,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> StreamState<T>
+where
+    T: JsonApiDeserialize,
+{
+    fn feed(&mut self, bytes: &[u8]) {
+        self.scanner.feed(bytes);
+    }
+
+    fn finish(&mut self) {
+        self.scanner.eof = true;
+    }
+
+    fn abort(&mut self) {
+        self.pending.clear();
+        self.finished = true;
+    }
+
+    fn next_step(&mut self) -> Step<T> {
+        loop {
+            if self.included_seen || self.finished {
+                if let Some((pointer, value)) = self.pending.pop_front() {
+                    return Step::Item(self.deserialize(&pointer, &value));
+                }
+            }
+
+            if self.finished {
+                return Step::Done;
+            }
+
+            let item = match self.scanner.next_event() {
+                Ok(None) => return Step::NeedData,
+                Ok(Some(Event::Member(key, value))) => match key.as_str() {
+                    "data" => {
+                        self.data_seen = true;
+
+                        match serde_json::from_slice::<Value>(value) {
+                            Ok(Value::Null) => continue,
+                            Ok(value) => ("/data".to_string(), value),
+                            Err(error) => return self.fail(Error::SerdeError(error).into()),
+                        }
+                    }
+                    "included" => {
+                        match serde_json::from_slice::<Vec<RawResource>>(value) {
+                            Ok(resources) => {
+                                self.included_map = resources.into();
+                                self.included_seen = true;
+                            }
+                            Err(error) => return self.fail(Error::SerdeError(error).into()),
+                        }
+
+                        continue;
+                    }
+                    "errors" => {
+                        return match serde_json::from_slice::<Vec<DocumentError>>(value) {
+                            Ok(errors) => {
+                                self.fail(crate::error::Error::DocumentError(errors, None))
+                            }
+                            Err(error) => self.fail(Error::SerdeError(error).into()),
+                        };
+                    }
+                    "meta" => {
+                        match serde_json::from_slice(value) {
+                            Ok(meta) => self.meta = meta,
+                            Err(error) => return self.fail(Error::SerdeError(error).into()),
+                        }
+
+                        continue;
+                    }
+                    "links" => {
+                        match serde_json::from_slice(value) {
+                            Ok(links) => self.links = links,
+                            Err(error) => return self.fail(Error::SerdeError(error).into()),
+                        }
+
+                        continue;
+                    }
+                    _ => continue,
+                },
+                Ok(Some(Event::Item(value))) => {
+                    self.data_seen = true;
+                    let pointer = format!("/data/{}", self.index);
+                    self.index += 1;
+
+                    match serde_json::from_slice::<Value>(value) {
+                        Ok(value) => (pointer, value),
+                        Err(error) => return self.fail(Error::SerdeError(error).into()),
+                    }
+                }
+                Ok(Some(Event::End)) => {
+                    self.finished = true;
+
+                    // Meta-only documents simply have no primary data to yield.
+                    if !self.data_seen && self.meta.is_none() {
+                        return Step::Item(Err(Error::IncompleteDocument.into()));
+                    }
+
+                    continue;
+                }
+                Err(error) => return self.fail(error.into()),
+            };
+
+            let (pointer, value) = item;
+
+            if self.included_seen {
+                return Step::Item(self.deserialize(&pointer, &value));
+            }
+
+            if !self.pending.is_empty() {
+                self.pending.push_back((pointer, value));
+                continue;
+            }
+
+            // The included resources may still follow, so resources which reference any of them
+            // are held back until they have been read.
+            match self.deserialize(&pointer, &value) {
+                Err(crate::error::Error::DeserializeError(Error::MissingResource { .. })) => {
+                    self.pending.push_back((pointer, value));
+                }
+                result => return Step::Item(result),
+            }
+        }
+    }
+
+    fn deserialize(&mut self, pointer: &str, value: &Value) -> Result<T, crate::error::Error> {
+        self.included_map
+            .scoped(pointer, |included_map| T::from_value(value, included_map))
+            .map_err(Into::into)
+    }
+
+    fn fail(&mut self, error: crate::error::Error) -> Step<T> {
+        self.abort();
+        Step::Item(Err(error))
+    }
+}
+
+enum Event<'a> {
+    /// A complete top-level member, except for `data` when it is an array.
+    Member(String, &'a [u8]),
+    /// A single item of the `data` array.
+    Item(&'a [u8]),
+    End,
+}
+
+#[derive(Default)]
+enum ScannerState {
+    #[default]
+    Start,
+    Key {
+        first: bool,
+    },
+    Colon(String),
+    Value(String),
+    Items {
+        first: bool,
+    },
+    Item,
+    NextKey,
+    Done,
+}
+
+/// Incremental scanner for the top level of a document.
+///
+/// The scanner only determines the boundaries of values, the values themselves are parsed by
+/// `serde_json` once they are complete.
+#[derive(Default)]
+struct Scanner {
+    buffer: Vec<u8>,
+    position: usize,
+    eof: bool,
+    state: ScannerState,
+    value: ValueScan,
+}
+
+impl Scanner {
+    fn feed(&mut self, bytes: &[u8]) {
+        self.buffer.drain(..self.position);
+        self.position = 0;
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Returns the next event, or `None` if more data is required.
+    fn next_event(&mut self) -> Result<Option<Event<'_>>, Error> {
+        loop {
+            match std::mem::take(&mut self.state) {
+                ScannerState::Start => match self.next_byte() {
+                    Some(b'{') => {
+                        self.position += 1;
+                        self.state = ScannerState::Key { first: true };
+                    }
+                    Some(_) => return Err(Error::InvalidType("Expected an object")),
+                    None => return self.need_data(ScannerState::Start),
+                },
+                ScannerState::Key { first } => match self.next_byte() {
+                    Some(b'}') if first => {
+                        self.position += 1;
+                        self.state = ScannerState::Done;
+                        return Ok(Some(Event::End));
+                    }
+                    Some(b'"') => {
+                        let Some(end) = self.scan_value()? else {
+                            return self.need_data(ScannerState::Key { first });
+                        };
+
+                        let key = serde_json::from_slice(&self.buffer[self.position..end])?;
+                        self.position = end;
+                        self.state = ScannerState::Colon(key);
+                    }
+                    Some(_) => return Err(syntax_error("expected member name")),
+                    None => return self.need_data(ScannerState::Key { first }),
+                },
+                ScannerState::Colon(key) => match self.next_byte() {
+                    Some(b':') => {
+                        self.position += 1;
+                        self.state = ScannerState::Value(key);
+                    }
+                    Some(_) => return Err(syntax_error("expected `:`")),
+                    None => return self.need_data(ScannerState::Colon(key)),
+                },
+                ScannerState::Value(key) => match self.next_byte() {
+                    Some(b'[') if key == "data" => {
+                        self.position += 1;
+                        self.state = ScannerState::Items { first: true };
+                    }
+                    Some(_) => {
+                        let Some(end) = self.scan_value()? else {
+                            return self.need_data(ScannerState::Value(key));
+                        };
+
+                        let start = self.position;
+                        self.position = end;
+                        self.state = ScannerState::NextKey;
+                        return Ok(Some(Event::Member(key, &self.buffer[start..end])));
+                    }
+                    None => return self.need_data(ScannerState::Value(key)),
+                },
+                ScannerState::Items { first } => match self.next_byte() {
+                    Some(b']') => {
+                        self.position += 1;
+                        self.state = ScannerState::NextKey;
+                    }
+                    Some(b',') if !first => {
+                        self.position += 1;
+                        self.state = ScannerState::Item;
+                    }
+                    Some(_) if first => {
+                        self.state = ScannerState::Item;
+                    }
+                    Some(_) => return Err(syntax_error("expected `,` or `]`")),
+                    None => return self.need_data(ScannerState::Items { first }),
+                },
+                ScannerState::Item => match self.next_byte() {
+                    Some(_) => {
+                        let Some(end) = self.scan_value()? else {
+                            return self.need_data(ScannerState::Item);
+                        };
+
+                        let start = self.position;
+                        self.position = end;
+                        self.state = ScannerState::Items { first: false };
+                        return Ok(Some(Event::Item(&self.buffer[start..end])));
+                    }
+                    None => return self.need_data(ScannerState::Item),
+                },
+                ScannerState::NextKey => match self.next_byte() {
+                    Some(b',') => {
+                        self.position += 1;
+                        self.state = ScannerState::Key { first: false };
+                    }
+                    Some(b'}') => {
+                        self.position += 1;
+                        self.state = ScannerState::Done;
+                        return Ok(Some(Event::End));
+                    }
+                    Some(_) => return Err(syntax_error("expected `,` or `}`")),
+                    None => return self.need_data(ScannerState::NextKey),
+                },
+                ScannerState::Done => {
+                    self.state = ScannerState::Done;
+                    return Ok(Some(Event::End));
+                }
+            }
+        }
+    }
+
+    fn need_data<'a>(&mut self, state: ScannerState) -> Result<Option<Event<'a>>, Error> {
+        if self.eof {
+            return Err(syntax_error("unexpected end of document"));
+        }
+
+        self.state = state;
+        Ok(None)
+    }
+
+    /// Skips whitespace and returns the next byte without consuming it.
+    fn next_byte(&mut self) -> Option<u8> {
+        while let Some(byte) = self.buffer.get(self.position) {
+            if !byte.is_ascii_whitespace() {
+                return Some(*byte);
+            }
+
+            self.position += 1;
+        }
+
+        None
+    }
+
+    /// Returns the end of the value starting at the current position, if it is complete.
+    fn scan_value(&mut self) -> Result<Option<usize>, Error> {
+        let bytes = &self.buffer[self.position..];
+
+        match self.value.scan(bytes, self.eof) {
+            Some(length) => {
+                self.value = ValueScan::default();
+                Ok(Some(self.position + length))
+            }
+            None if self.eof => Err(syntax_error("unexpected end of document")),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Resumable search for the end of a single JSON value.
+#[derive(Default)]
+struct ValueScan {
+    offset: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl ValueScan {
+    fn scan(&mut self, bytes: &[u8], eof: bool) -> Option<usize> {
+        while let Some(&byte) = bytes.get(self.offset) {
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+
+                    if self.depth == 0 {
+                        return Some(self.offset + 1);
+                    }
+                }
+
+                self.offset += 1;
+                continue;
+            }
+
+            match byte {
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' if self.depth == 0 => return Some(self.offset),
+                b'}' | b']' => {
+                    self.depth -= 1;
+
+                    if self.depth == 0 {
+                        return Some(self.offset + 1);
+                    }
+                }
+                b',' if self.depth == 0 => return Some(self.offset),
+                byte if self.depth == 0 && byte.is_ascii_whitespace() => return Some(self.offset),
+                _ => {}
+            }
+
+            self.offset += 1;
+        }
+
+        // A number, boolean or null can only be terminated by the end of the document.
+        if eof && self.depth == 0 && !self.in_string && self.offset > 0 {
+            return Some(self.offset);
+        }
+
+        None
+    }
+}
+
+fn syntax_error(message: &str) -> Error {
+    Error::SerdeError(serde_json::Error::custom(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(json: &str, chunk_size: usize) -> Vec<String> {
+        let mut scanner = Scanner::default();
+        let mut chunks = json.as_bytes().chunks(chunk_size);
+        let mut events = Vec::new();
+
+        loop {
+            match scanner.next_event().unwrap() {
+                Some(Event::Member(key, value)) => {
+                    events.push(format!("{}={}", key, String::from_utf8_lossy(value)))
+                }
+                Some(Event::Item(value)) => {
+                    events.push(format!("item={}", String::from_utf8_lossy(value)))
+                }
+                Some(Event::End) => return events,
+                None => match chunks.next() {
+                    Some(chunk) => scanner.feed(chunk),
+                    None => scanner.eof = true,
+                },
+            }
+        }
+    }
+
+    #[test]
+    fn test_scan_members_and_items() {
+        let json = r#" { "meta" : {"a": [1, "]}"]}, "data": [ {"id": "1"} , 2, "x\"y", null ],
+            "jsonapi": true, "k\"ey": -1.5 } "#;
+
+        let expected = [
+            r#"meta={"a": [1, "]}"]}"#,
+            r#"item={"id": "1"}"#,
+            "item=2",
+            r#"item="x\"y""#,
+            "item=null",
+            "jsonapi=true",
+            r#"k"ey=-1.5"#,
+        ];
+
+        for chunk_size in [1, 2, 3, 7, 1024] {
+            assert_eq!(
+                events(json, chunk_size),
+                expected,
+                "chunk size {}",
+                chunk_size
+            );
+        }
+    }
+
+    #[test]
+    fn test_scan_empty() {
+        assert!(events("{}", 1).is_empty());
+        assert_eq!(events(r#"{"data": []}"#, 1), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_scan_unexpected_end() {
+        let mut scanner = Scanner::default();
+        scanner.feed(br#"{"data": [{"id": "1"}"#);
+        scanner.eof = true;
+
+        assert!(matches!(scanner.next_event(), Ok(Some(Event::Item(_)))));
+        assert!(scanner.next_event().is_err());
+    }
+
+    #[test]
+    fn test_scan_invalid_document() {
+        let mut scanner = Scanner::default();
+        scanner.feed(b"[]");
+        assert!(scanner.next_event().is_err());
+    }
+}
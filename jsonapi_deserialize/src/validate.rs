@@ -0,0 +1,562 @@
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fmt;
+
+/// A rule of the JSON:API 1.1 specification which a document can violate.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Rule {
+    /// The document must be an object.
+    TopLevelObject,
+    /// The document must contain at least one of `data`, `errors` or `meta`.
+    TopLevelMembers,
+    /// `data` and `errors` must not coexist.
+    DataAndErrors,
+    /// `included` must not be present without `data`.
+    IncludedWithoutData,
+    /// Primary data must be `null`, a resource object or an array of resource objects.
+    PrimaryData,
+    /// `errors` must be an array of error objects.
+    ErrorObjects,
+    /// Resources must be objects with a string `type`, and a string `id` or `lid`.
+    ResourceIdentification,
+    /// Attributes and relationships must be objects sharing no names, and must not be named
+    /// `type` or `id`.
+    ResourceFields,
+    /// Objects within attributes must not contain `relationships` or `links` members.
+    ComplexAttributes,
+    /// Relationships must contain at least one of `links`, `data` or `meta`.
+    RelationshipMembers,
+    /// Resource linkage must be `null`, a resource identifier or an array of them.
+    ResourceLinkage,
+    /// Resource identifiers must have a string `type`, and a string `id` or `lid`.
+    ResourceIdentifier,
+    /// A document must not contain more than one resource with the same `type` and `id`.
+    UniqueResources,
+    /// Every included resource must be identified by a resource identifier in the document.
+    FullLinkage,
+    /// Member names must only consist of allowed characters.
+    MemberNames,
+}
+
+impl Rule {
+    /// Returns the identifier of the rule.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::TopLevelObject => "top-level-object",
+            Rule::TopLevelMembers => "top-level-members",
+            Rule::DataAndErrors => "data-and-errors",
+            Rule::IncludedWithoutData => "included-without-data",
+            Rule::PrimaryData => "primary-data",
+            Rule::ErrorObjects => "error-objects",
+            Rule::ResourceIdentification => "resource-identification",
+            Rule::ResourceFields => "resource-fields",
+            Rule::ComplexAttributes => "complex-attributes",
+            Rule::RelationshipMembers => "relationship-members",
+            Rule::ResourceLinkage => "resource-linkage",
+            Rule::ResourceIdentifier => "resource-identifier",
+            Rule::UniqueResources => "unique-resources",
+            Rule::FullLinkage => "full-linkage",
+            Rule::MemberNames => "member-names",
+        }
+    }
+
+    /// Returns the anchor of the specification section defining the rule.
+    pub fn section(&self) -> &'static str {
+        match self {
+            Rule::TopLevelObject
+            | Rule::TopLevelMembers
+            | Rule::DataAndErrors
+            | Rule::IncludedWithoutData
+            | Rule::PrimaryData => "document-top-level",
+            Rule::ErrorObjects => "error-objects",
+            Rule::ResourceIdentification => "document-resource-object-identification",
+            Rule::ResourceFields => "document-resource-object-fields",
+            Rule::ComplexAttributes => "document-resource-object-attributes",
+            Rule::RelationshipMembers => "document-resource-object-relationships",
+            Rule::ResourceLinkage => "document-resource-object-linkage",
+            Rule::ResourceIdentifier => "document-resource-identifier-objects",
+            Rule::UniqueResources | Rule::FullLinkage => "document-compound-documents",
+            Rule::MemberNames => "document-member-names",
+        }
+    }
+
+    /// Returns the URL of the specification section defining the rule.
+    pub fn url(&self) -> String {
+        format!("https://jsonapi.org/format/1.1/#{}", self.section())
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// A violation of the specification found in a document.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SpecViolation {
+    pub rule: Rule,
+    /// JSON pointer to the offending value within the document.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for SpecViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {} ({}, see {})",
+            self.message,
+            self.pointer,
+            self.rule,
+            self.rule.url()
+        )
+    }
+}
+
+/// Checks a document against the structural rules of the JSON:API 1.1 specification.
+///
+/// Documents which are not valid JSON are reported as a violation of [`Rule::TopLevelObject`].
+pub fn validate_document(json: &str) -> Vec<SpecViolation> {
+    match serde_json::from_str::<Value>(json) {
+        Ok(value) => validate_document_value(&value),
+        Err(error) => vec![SpecViolation {
+            rule: Rule::TopLevelObject,
+            pointer: String::new(),
+            message: format!("Document is not valid JSON: {}", error),
+        }],
+    }
+}
+
+pub(crate) fn validate_document_value(value: &Value) -> Vec<SpecViolation> {
+    let mut validator = Validator::default();
+    validator.validate_document(value);
+    validator.violations
+}
+
+#[derive(Default)]
+struct Validator<'a> {
+    violations: Vec<SpecViolation>,
+    resources: HashSet<(&'a str, &'a str)>,
+    identified: HashSet<(&'a str, &'a str)>,
+}
+
+impl<'a> Validator<'a> {
+    fn report(&mut self, rule: Rule, pointer: &str, message: impl Into<String>) {
+        self.violations.push(SpecViolation {
+            rule,
+            pointer: pointer.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn validate_document(&mut self, value: &'a Value) {
+        let Some(document) = value.as_object() else {
+            self.report(Rule::TopLevelObject, "", "Document must be an object");
+            return;
+        };
+
+        let data = document.get("data");
+
+        if data.is_none() && !document.contains_key("errors") && !document.contains_key("meta") {
+            self.report(
+                Rule::TopLevelMembers,
+                "",
+                "Document must contain at least one of data, errors or meta",
+            );
+        }
+
+        if data.is_some() && document.contains_key("errors") {
+            self.report(
+                Rule::DataAndErrors,
+                "",
+                "Document must not contain both data and errors",
+            );
+        }
+
+        match document.get("errors") {
+            Some(Value::Array(errors)) => {
+                for (index, error) in errors.iter().enumerate() {
+                    if !error.is_object() {
+                        self.report(
+                            Rule::ErrorObjects,
+                            &format!("/errors/{}", index),
+                            "Error must be an object",
+                        );
+                    }
+                }
+            }
+            Some(_) => self.report(Rule::ErrorObjects, "/errors", "Errors must be an array"),
+            None => {}
+        }
+
+        match data {
+            Some(Value::Null) | None => {}
+            Some(resource @ Value::Object(_)) => {
+                self.validate_resource(resource, "/data", true);
+            }
+            Some(Value::Array(resources)) => {
+                for (index, resource) in resources.iter().enumerate() {
+                    self.validate_resource(resource, &format!("/data/{}", index), true);
+                }
+            }
+            Some(_) => self.report(
+                Rule::PrimaryData,
+                "/data",
+                "Primary data must be null, an object or an array",
+            ),
+        }
+
+        let Some(included) = document.get("included") else {
+            return;
+        };
+
+        if data.is_none() {
+            self.report(
+                Rule::IncludedWithoutData,
+                "/included",
+                "Document must not contain included resources without data",
+            );
+        }
+
+        let Some(included) = included.as_array() else {
+            self.report(
+                Rule::ResourceIdentification,
+                "/included",
+                "Included resources must be an array",
+            );
+            return;
+        };
+
+        let mut keys = Vec::with_capacity(included.len());
+
+        for (index, resource) in included.iter().enumerate() {
+            let pointer = format!("/included/{}", index);
+            keys.push(self.validate_resource(resource, &pointer, false));
+        }
+
+        for (index, key) in keys.into_iter().enumerate() {
+            if let Some(key) = key {
+                if !self.identified.contains(&key) {
+                    self.report(
+                        Rule::FullLinkage,
+                        &format!("/included/{}", index),
+                        format!(
+                            "Included resource {} {} is not identified anywhere in the document",
+                            key.0, key.1
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Validates a resource and returns its type and ID.
+    fn validate_resource(
+        &mut self,
+        value: &'a Value,
+        pointer: &str,
+        primary: bool,
+    ) -> Option<(&'a str, &'a str)> {
+        let Some(resource) = value.as_object() else {
+            self.report(
+                Rule::ResourceIdentification,
+                pointer,
+                "Resource must be an object",
+            );
+            return None;
+        };
+
+        let key = self.validate_identification(resource, pointer, Rule::ResourceIdentification);
+
+        if let Some(key) = key {
+            if !self.resources.insert(key) {
+                self.report(
+                    Rule::UniqueResources,
+                    pointer,
+                    format!("Resource {} {} is contained more than once", key.0, key.1),
+                );
+            }
+
+            // Resource identifiers as primary data identify included resources.
+            if primary {
+                self.identified.insert(key);
+            }
+        }
+
+        let attributes = match resource.get("attributes") {
+            Some(Value::Object(attributes)) => Some(attributes),
+            Some(_) => {
+                self.report(
+                    Rule::ResourceFields,
+                    &format!("{}/attributes", pointer),
+                    "Attributes must be an object",
+                );
+                None
+            }
+            None => None,
+        };
+        let relationships = match resource.get("relationships") {
+            Some(Value::Object(relationships)) => Some(relationships),
+            Some(_) => {
+                self.report(
+                    Rule::ResourceFields,
+                    &format!("{}/relationships", pointer),
+                    "Relationships must be an object",
+                );
+                None
+            }
+            None => None,
+        };
+
+        if let Some(attributes) = attributes {
+            let pointer = format!("{}/attributes", pointer);
+
+            for (name, value) in attributes {
+                let pointer = format!("{}/{}", pointer, escape(name));
+                self.validate_field_name(name, &pointer);
+
+                if relationships.is_some_and(|relationships| relationships.contains_key(name)) {
+                    self.report(
+                        Rule::ResourceFields,
+                        &pointer,
+                        format!("Field {} is both an attribute and a relationship", name),
+                    );
+                }
+
+                self.validate_attribute(value, &pointer);
+            }
+        }
+
+        if let Some(relationships) = relationships {
+            let pointer = format!("{}/relationships", pointer);
+
+            for (name, value) in relationships {
+                let pointer = format!("{}/{}", pointer, escape(name));
+                self.validate_field_name(name, &pointer);
+                self.validate_relationship(value, &pointer, key);
+            }
+        }
+
+        key
+    }
+
+    /// Validates the `type`, `id` and `lid` members and returns the type and ID.
+    fn validate_identification(
+        &mut self,
+        object: &'a Map<String, Value>,
+        pointer: &str,
+        rule: Rule,
+    ) -> Option<(&'a str, &'a str)> {
+        let kind = match object.get("type") {
+            Some(Value::String(kind)) => Some(kind.as_str()),
+            Some(_) => {
+                self.report(rule, &format!("{}/type", pointer), "Type must be a string");
+                None
+            }
+            None => {
+                self.report(rule, pointer, "Type is missing");
+                None
+            }
+        };
+
+        let id = match object.get("id") {
+            Some(Value::String(id)) => Some(id.as_str()),
+            Some(_) => {
+                self.report(rule, &format!("{}/id", pointer), "ID must be a string");
+                None
+            }
+            None => None,
+        };
+
+        match object.get("lid") {
+            Some(Value::String(_)) => {}
+            Some(_) => self.report(
+                rule,
+                &format!("{}/lid", pointer),
+                "Local ID must be a string",
+            ),
+            None if !object.contains_key("id") => {
+                self.report(rule, pointer, "ID is missing");
+            }
+            None => {}
+        }
+
+        Some((kind?, id?))
+    }
+
+    fn validate_field_name(&mut self, name: &str, pointer: &str) {
+        if name == "type" || name == "id" {
+            self.report(
+                Rule::ResourceFields,
+                pointer,
+                format!("Field must not be named {}", name),
+            );
+        }
+
+        self.validate_member_name(name, pointer);
+    }
+
+    fn validate_member_name(&mut self, name: &str, pointer: &str) {
+        if !is_valid_member_name(name) {
+            self.report(
+                Rule::MemberNames,
+                pointer,
+                format!("Member name {:?} contains disallowed characters", name),
+            );
+        }
+    }
+
+    fn validate_attribute(&mut self, value: &Value, pointer: &str) {
+        match value {
+            Value::Object(object) => {
+                for (name, value) in object {
+                    let pointer = format!("{}/{}", pointer, escape(name));
+                    self.validate_member_name(name, &pointer);
+
+                    if name == "relationships" || name == "links" {
+                        self.report(
+                            Rule::ComplexAttributes,
+                            &pointer,
+                            format!("Attributes must not contain a {} member", name),
+                        );
+                    }
+
+                    self.validate_attribute(value, &pointer);
+                }
+            }
+            Value::Array(values) => {
+                for (index, value) in values.iter().enumerate() {
+                    self.validate_attribute(value, &format!("{}/{}", pointer, index));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn validate_relationship(
+        &mut self,
+        value: &'a Value,
+        pointer: &str,
+        owner: Option<(&'a str, &'a str)>,
+    ) {
+        let Some(relationship) = value.as_object() else {
+            self.report(
+                Rule::RelationshipMembers,
+                pointer,
+                "Relationship must be an object",
+            );
+            return;
+        };
+
+        if !["links", "data", "meta"]
+            .iter()
+            .any(|member| relationship.contains_key(*member))
+        {
+            self.report(
+                Rule::RelationshipMembers,
+                pointer,
+                "Relationship must contain at least one of links, data or meta",
+            );
+        }
+
+        let pointer = format!("{}/data", pointer);
+
+        match relationship.get("data") {
+            Some(Value::Null) | None => {}
+            Some(Value::Object(identifier)) => {
+                self.validate_identifier(identifier, &pointer, owner)
+            }
+            Some(Value::Array(identifiers)) => {
+                for (index, identifier) in identifiers.iter().enumerate() {
+                    let pointer = format!("{}/{}", pointer, index);
+
+                    match identifier.as_object() {
+                        Some(identifier) => self.validate_identifier(identifier, &pointer, owner),
+                        None => self.report(
+                            Rule::ResourceIdentifier,
+                            &pointer,
+                            "Resource identifier must be an object",
+                        ),
+                    }
+                }
+            }
+            Some(_) => self.report(
+                Rule::ResourceLinkage,
+                &pointer,
+                "Resource linkage must be null, an object or an array",
+            ),
+        }
+    }
+
+    fn validate_identifier(
+        &mut self,
+        identifier: &'a Map<String, Value>,
+        pointer: &str,
+        owner: Option<(&'a str, &'a str)>,
+    ) {
+        let key = self.validate_identification(identifier, pointer, Rule::ResourceIdentifier);
+
+        // A resource referring to itself does not establish linkage.
+        if let Some(key) = key.filter(|key| Some(*key) != owner) {
+            self.identified.insert(key);
+        }
+    }
+}
+
+/// Whether a member name only consists of the characters allowed by the specification.
+///
+/// `@`-members are not checked, and extension members are checked on both sides of the colon.
+fn is_valid_member_name(name: &str) -> bool {
+    if name.starts_with('@') {
+        return true;
+    }
+
+    match name.split_once(':') {
+        Some((namespace, member)) => is_valid_name(namespace) && is_valid_name(member),
+        None => is_valid_name(name),
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let globally_allowed = |c: char| c.is_ascii_alphanumeric() || c >= '\u{80}';
+
+    let (Some(first), Some(last)) = (name.chars().next(), name.chars().last()) else {
+        return false;
+    };
+
+    globally_allowed(first)
+        && globally_allowed(last)
+        && name
+            .chars()
+            .all(|c| globally_allowed(c) || c == '-' || c == '_' || c == ' ')
+}
+
+/// Escapes a member name for use in a JSON pointer, as described in RFC 6901.
+fn escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_member_names() {
+        for name in [
+            "title",
+            "a",
+            "first-name",
+            "first_name",
+            "ünïcode",
+            "ext:member",
+            "@meta",
+        ] {
+            assert!(is_valid_member_name(name), "{} should be valid", name);
+        }
+
+        for name in ["", "-title", "title_", "a.b", "a+b", "ext:", ":member"] {
+            assert!(!is_valid_member_name(name), "{} should be invalid", name);
+        }
+    }
+}
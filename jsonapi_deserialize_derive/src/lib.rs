@@ -1,8 +1,9 @@
+use darling::util::Override;
 use darling::{ast, FromDeriveInput, FromField, FromMeta};
 use heck::{ToLowerCamelCase, ToPascalCase, ToSnakeCase};
 use proc_macro::TokenStream;
 use proc_macro2::Ident;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 use syn::{parse_macro_input, DeriveInput, Generics, Type};
 
 #[proc_macro_derive(JsonApiDeserialize, attributes(json_api))]
@@ -11,21 +12,16 @@ pub fn json_api_deserialize(input: TokenStream) -> TokenStream {
     impl_json_api_deserialize(&input).into()
 }
 
-#[derive(Debug, FromMeta)]
+#[derive(Debug, Default, FromMeta)]
 #[darling(default)]
 #[allow(clippy::enum_variant_names)]
 enum RenameAll {
+    #[default]
     CamelCase,
     PascalCase,
     SnakeCase,
 }
 
-impl Default for RenameAll {
-    fn default() -> Self {
-        Self::CamelCase
-    }
-}
-
 #[derive(Debug, FromDeriveInput)]
 #[darling(attributes(json_api), supports(struct_any))]
 struct InputReceiver {
@@ -36,9 +32,10 @@ struct InputReceiver {
     resource_type: Option<String>,
     #[darling(default)]
     rename_all: RenameAll,
+    validate: Option<syn::Path>,
 }
 
-#[derive(Debug, FromMeta)]
+#[derive(Clone, Copy, Debug, FromMeta, PartialEq)]
 enum Relationship {
     Single,
     Optional,
@@ -49,31 +46,68 @@ enum Relationship {
 #[darling(attributes(json_api))]
 struct FieldReceiver {
     ident: Option<Ident>,
-    #[allow(dead_code)]
     ty: Type,
-    relationship: Option<Relationship>,
+    relationship: Option<Override<Relationship>>,
     resource: Option<Type>,
     rename: Option<String>,
-    #[darling(default)]
-    default: bool,
+    links: Option<Override<String>>,
+    validate: Option<syn::Path>,
+    default: Option<Override<syn::Path>>,
     #[darling(default)]
     optional: bool,
+    #[darling(default)]
+    skip: bool,
+    from_meta: Option<String>,
+    path: Option<syn::LitStr>,
 }
 
+impl FieldReceiver {
+    /// Whether any `json_api` option is given on the field.
+    fn has_options(&self) -> bool {
+        self.relationship.is_some()
+            || self.resource.is_some()
+            || self.rename.is_some()
+            || self.links.is_some()
+            || self.validate.is_some()
+            || self.default.is_some()
+            || self.optional
+            || self.skip
+            || self.from_meta.is_some()
+            || self.path.is_some()
+    }
+}
+
+/// Reads a member of the resource's `attributes`, or of another object such as its `meta`.
+///
+/// The member is looked up through each segment of `path` in turn, and `missing` names it in
+/// the error when it is absent.
 fn get_attribute_tokens(
     field_name: &Ident,
-    json_field_name: &str,
-    default: bool,
+    object: &str,
+    path: &[String],
+    missing: &str,
+    default: Option<&proc_macro2::TokenStream>,
     optional: bool,
 ) -> proc_macro2::TokenStream {
-    if !(default || optional) {
+    let missing_object = match object {
+        "attributes" => quote! { Error::MissingAttributes },
+        _ => quote! { Error::MissingField(#missing) },
+    };
+
+    let (first, rest) = path.split_first().expect("member path is never empty");
+    let lookup = quote! {
+        .get(#first)
+        #(.and_then(|value| value.get(#rest)))*
+    };
+
+    if !(default.is_some() || optional) {
         return quote! {
             let #field_name = serde_json::from_value(
                 data
-                    .get("attributes")
-                    .ok_or(Error::MissingAttributes)?
-                    .get(#json_field_name)
-                    .ok_or(Error::MissingField(stringify!(#field_name)))?
+                    .get(#object)
+                    .ok_or(#missing_object)?
+                    #lookup
+                    .ok_or(Error::MissingField(#missing))?
                     .clone(),
             )?;
         };
@@ -81,16 +115,16 @@ fn get_attribute_tokens(
 
     let mut tokens = quote! {
         let #field_name = data
-            .get("attributes")
-            .and_then(|attrs| attrs.get(#json_field_name))
+            .get(#object)
+            .and_then(|attrs| attrs #lookup)
             .cloned();
     };
 
-    if default {
+    if let Some(default) = default {
         tokens.extend(quote! {
             let #field_name = match #field_name {
                 Some(value) => serde_json::from_value(value)?,
-                None => Default::default(),
+                None => #default,
             };
         });
     } else {
@@ -109,14 +143,14 @@ fn get_relationship_tokens(
     field_name: &Ident,
     json_field_name: &str,
     relationship_type: &str,
-    default: bool,
+    default: Option<&proc_macro2::TokenStream>,
     optional: bool,
     lookup_tokens: Option<proc_macro2::TokenStream>,
 ) -> proc_macro2::TokenStream {
     let ty = format_ident!("{}", relationship_type);
     let ty = quote! { jsonapi_deserialize::#ty };
 
-    if !(default || optional) {
+    if !(default.is_some() || optional) {
         return quote! {
             let #field_name = serde_json::from_value::<#ty>(
                 data
@@ -138,7 +172,7 @@ fn get_relationship_tokens(
             .cloned();
     };
 
-    if default {
+    if let Some(default) = default {
         tokens.extend(quote! {
             let #field_name = match #field_name {
                 Some(value) => {
@@ -146,7 +180,7 @@ fn get_relationship_tokens(
                     #lookup_tokens
                     #field_name.into()
                 },
-                None => Default::default(),
+                None => #default,
             };
         });
     } else {
@@ -165,24 +199,431 @@ fn get_relationship_tokens(
     tokens
 }
 
+fn get_links_tokens(field_name: &Ident, links: &Override<String>) -> proc_macro2::TokenStream {
+    let links_value = match links {
+        Override::Inherit => quote! { data.get("links") },
+        Override::Explicit(relationship) => quote! {
+            data
+                .get("relationships")
+                .and_then(|rels| rels.get(#relationship))
+                .and_then(|rel| rel.get("links"))
+        },
+    };
+
+    quote! {
+        let #field_name = match #links_value {
+            Some(value) => serde_json::from_value(value.clone())?,
+            None => Default::default(),
+        };
+    }
+}
+
+/// Calls a validation hook with a reference to `value`, failing with the current pointer.
+///
+/// The value is bound to its type first, as the hook may accept any reference it derefs to.
+fn get_validation_tokens(
+    validate: &Option<syn::Path>,
+    value: &Ident,
+    ty: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match validate {
+        Some(validate) => quote! {
+            let #value: #ty = #value;
+
+            if let Err(message) = #validate(&#value) {
+                return Err(Error::Validation {
+                    pointer: included_map.pointer().to_string(),
+                    message: message.to_string(),
+                });
+            }
+        },
+        None => quote! {},
+    }
+}
+
+/// Returns `T` for a field of type `Option<T>`.
+///
+/// Resolved relationships with a default are converted into the field type, which can't be
+/// inferred when the field wraps them in an `Option`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = last_segment(ty).filter(|segment| segment.ident == "Option")?;
+
+    match type_arguments(segment).as_slice() {
+        [inner] => Some(inner),
+        _ => None,
+    }
+}
+
+/// Returns the last path segment of a type, e.g. `Arc` for `std::sync::Arc<T>`.
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+/// Returns the generic type arguments of a path segment.
+fn type_arguments(segment: &syn::PathSegment) -> Vec<&Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Infers the cardinality of a relationship and the type of its items from the field type.
+///
+/// Returns whether the shape of the type was recognized, as unknown types are assumed to be
+/// to-one relationships.
+fn infer_cardinality(ty: &Type) -> (Relationship, &Type, bool) {
+    if let Some(segment) = last_segment(ty) {
+        match (
+            segment.ident.to_string().as_str(),
+            type_arguments(segment).as_slice(),
+        ) {
+            ("Option", [item]) => return (Relationship::Optional, item, true),
+            (
+                "Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet" | "IndexSet",
+                [item, ..],
+            ) => return (Relationship::Multiple, item, true),
+            ("HashMap" | "BTreeMap" | "IndexMap", [_, item, ..]) => {
+                return (Relationship::Multiple, item, true)
+            }
+            ("Box", [Type::Slice(slice)]) => return (Relationship::Multiple, &slice.elem, true),
+            ("SmallVec", [Type::Array(array)]) => {
+                return (Relationship::Multiple, &array.elem, true)
+            }
+            _ => {}
+        }
+    }
+
+    (Relationship::Single, ty, false)
+}
+
+/// Infers the resource a relationship item points to, or `None` for references.
+///
+/// Items wrapped in `Linked` point to the resource of the wrapped item.
+///
+/// Returns whether the item type was recognized, as unknown types are assumed to be resources
+/// which are cloned on resolution.
+fn infer_resource(ty: &Type) -> (Option<Type>, bool) {
+    if let Some(segment) = last_segment(ty) {
+        match (
+            segment.ident.to_string().as_str(),
+            type_arguments(segment).as_slice(),
+        ) {
+            ("Reference", []) => return (None, true),
+            ("Arc" | "Rc" | "Box", [resource]) => return (Some((*resource).clone()), true),
+            ("Linked", [item, ..]) => return infer_resource(item),
+            _ => {}
+        }
+    }
+
+    (Some(ty.clone()), false)
+}
+
+fn cardinality_name(relationship: Relationship) -> &'static str {
+    match relationship {
+        Relationship::Single => "single",
+        Relationship::Optional => "optional",
+        Relationship::Multiple => "multiple",
+    }
+}
+
+/// Determines the cardinality and resource of a relationship field.
+///
+/// Values which are not given explicitly are inferred from the field type, explicit values are
+/// validated against it as far as the type is recognized.
+fn resolve_relationship(
+    field: &FieldReceiver,
+    relationship: &Override<Relationship>,
+) -> Result<(Relationship, Option<Type>), darling::Error> {
+    let ty = match field.optional {
+        true => option_inner(&field.ty).ok_or_else(|| {
+            darling::Error::custom("optional relationships must be typed as `Option<T>`")
+                .with_span(&field.ty)
+        })?,
+        false => &field.ty,
+    };
+
+    let (mut cardinality, mut item, mut recognized) = infer_cardinality(ty);
+
+    if let Override::Explicit(explicit) = relationship {
+        // Defaults of to-one and to-many relationships may be wrapped in an `Option`.
+        if *explicit != cardinality
+            && field.default.is_some()
+            && cardinality == Relationship::Optional
+        {
+            (cardinality, item, recognized) = infer_cardinality(item);
+        }
+
+        if *explicit != cardinality {
+            if recognized || infer_resource(item).1 {
+                return Err(darling::Error::custom(format!(
+                    "relationship = \"{}\" does not match the field type, which implies relationship = \"{}\"",
+                    cardinality_name(*explicit),
+                    cardinality_name(cardinality),
+                ))
+                .with_span(&field.ty));
+            }
+
+            return Ok((*explicit, field.resource.clone()));
+        }
+    }
+
+    let (resource, recognized) = infer_resource(item);
+
+    match (&field.resource, resource) {
+        (None, resource) => Ok((cardinality, resource)),
+        (Some(explicit), None) if recognized => Err(darling::Error::custom(
+            "a resource is given, but the field is typed as a `Reference`",
+        )
+        .with_span(explicit)),
+        (Some(explicit), Some(resource))
+            if recognized
+                && last_segment(explicit).map(|segment| &segment.ident)
+                    != last_segment(&resource).map(|segment| &segment.ident) =>
+        {
+            Err(darling::Error::custom(format!(
+                "resource = \"{}\" does not match the field type",
+                quote! { #explicit },
+            ))
+            .with_span(explicit))
+        }
+        (Some(explicit), _) => Ok((cardinality, Some(explicit.clone()))),
+    }
+}
+
+/// Escapes a member name for use as a JSON pointer reference token, as described in RFC 6901.
+fn pointer_segment(name: &str) -> String {
+    format!("/{}", name.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Splits the `path` of a nested attribute into member names, accepting either dotted notation
+/// like `address.geo.lat` or a JSON pointer like `/address/geo/lat`.
+fn parse_member_path(path: &str) -> Option<Vec<String>> {
+    let segments: Vec<String> = match path.strip_prefix('/') {
+        Some(pointer) => pointer
+            .split('/')
+            .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+        None => path.split('.').map(str::to_string).collect(),
+    };
+
+    if segments.iter().any(String::is_empty) {
+        return None;
+    }
+
+    Some(segments)
+}
+
+/// Wraps the initializer of a field, so that errors are reported with the pointer of the member.
+fn get_scoped_tokens(
+    field_name: &Ident,
+    pointer: &str,
+    tokens: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote! {
+        let #field_name = match included_map.scoped(
+            #pointer,
+            |included_map| -> Result<_, Error> {
+                #tokens
+                Ok(#field_name)
+            },
+        ) {
+            Ok(value) => Some(value),
+            Err(error) if error.is_reported() => None,
+            Err(error) => return Err(error),
+        };
+    }
+}
+
+/// Checks that the value is a resource object of the given type, binding the object to `data`.
+fn get_type_check_tokens(resource_type: &str) -> proc_macro2::TokenStream {
+    quote! {
+        let data = value.as_object().ok_or(Error::InvalidType("Expected an object"))?;
+
+        let resource_type: String = serde_json::from_value(
+            data
+                .get("type")
+                .ok_or_else(|| Error::MissingResourceType)?
+                .clone(),
+        )?;
+
+        if resource_type != #resource_type {
+            return Err(Error::ResourceTypeMismatch {
+                expected: #resource_type.to_string(),
+                found: resource_type,
+            });
+        }
+    }
+}
+
+/// Delegates newtype structs to the wrapped resource.
+///
+/// When a resource type is given, it replaces the one of the wrapped resource, which is then
+/// deserialized as if it had its own type.
+fn impl_newtype(
+    struct_name: &Ident,
+    resource_type: Option<&str>,
+    validate: &Option<syn::Path>,
+    fields: &ast::Fields<FieldReceiver>,
+) -> proc_macro2::TokenStream {
+    let inner = match fields.fields.as_slice() {
+        [field] if field.has_options() => {
+            return darling::Error::custom(
+                "`json_api` options are not supported on the field of a newtype",
+            )
+            .with_span(&field.ty)
+            .write_errors()
+        }
+        [field] => &field.ty,
+        _ => {
+            return darling::Error::custom("tuple structs must have exactly one field")
+                .with_span(struct_name)
+                .write_errors()
+        }
+    };
+
+    let validation_tokens =
+        get_validation_tokens(validate, &format_ident!("resource"), quote! { Self });
+
+    let Some(resource_type) = resource_type else {
+        return quote! {
+            impl jsonapi_deserialize::JsonApiDeserialize for #struct_name {
+                const DESCRIPTOR: Option<fn() -> &'static jsonapi_deserialize::ResourceDescriptor> =
+                    <#inner as jsonapi_deserialize::JsonApiDeserialize>::DESCRIPTOR;
+
+                fn from_value(
+                    value: &serde_json::Value,
+                    included_map: &mut jsonapi_deserialize::IncludedMap,
+                ) -> Result<Self, jsonapi_deserialize::DeserializeError> {
+                    use jsonapi_deserialize::DeserializeError as Error;
+
+                    let resource = Self(
+                        <#inner as jsonapi_deserialize::JsonApiDeserialize>::from_value(value, included_map)?,
+                    );
+
+                    #validation_tokens
+
+                    Ok(resource)
+                }
+            }
+
+            // The higher-ranked bound is only checked on use, so wrapped resources which do not
+            // implement `JsonApiResource` can still be deserialized.
+            impl jsonapi_deserialize::JsonApiResource for #struct_name
+            where
+                for<'a> #inner: jsonapi_deserialize::JsonApiResource,
+            {
+                const TYPE: &'static str = <#inner as jsonapi_deserialize::JsonApiResource>::TYPE;
+
+                fn descriptor() -> &'static jsonapi_deserialize::ResourceDescriptor {
+                    <#inner as jsonapi_deserialize::JsonApiResource>::descriptor()
+                }
+            }
+        };
+    };
+
+    let type_check_tokens = get_type_check_tokens(resource_type);
+
+    quote! {
+        impl jsonapi_deserialize::JsonApiDeserialize for #struct_name {
+            const DESCRIPTOR: Option<fn() -> &'static jsonapi_deserialize::ResourceDescriptor> =
+                Some(<Self as jsonapi_deserialize::JsonApiResource>::descriptor);
+
+            fn from_value(
+                value: &serde_json::Value,
+                included_map: &mut jsonapi_deserialize::IncludedMap,
+            ) -> Result<Self, jsonapi_deserialize::DeserializeError> {
+                use jsonapi_deserialize::DeserializeError as Error;
+
+                #type_check_tokens
+
+                let mut data = data.clone();
+                data.insert(
+                    "type".to_string(),
+                    serde_json::Value::String(
+                        <#inner as jsonapi_deserialize::JsonApiResource>::TYPE.to_string(),
+                    ),
+                );
+
+                let resource = Self(
+                    <#inner as jsonapi_deserialize::JsonApiDeserialize>::from_value(
+                        &serde_json::Value::Object(data),
+                        included_map,
+                    )?,
+                );
+
+                #validation_tokens
+
+                Ok(resource)
+            }
+        }
+
+        impl jsonapi_deserialize::JsonApiResource for #struct_name {
+            const TYPE: &'static str = #resource_type;
+
+            fn descriptor() -> &'static jsonapi_deserialize::ResourceDescriptor {
+                static DESCRIPTOR: std::sync::OnceLock<jsonapi_deserialize::ResourceDescriptor> =
+                    std::sync::OnceLock::new();
+
+                DESCRIPTOR.get_or_init(|| {
+                    let inner = <#inner as jsonapi_deserialize::JsonApiResource>::descriptor();
+
+                    jsonapi_deserialize::ResourceDescriptor {
+                        kind: #resource_type,
+                        attributes: inner.attributes,
+                        relationships: inner.relationships,
+                    }
+                })
+            }
+        }
+    }
+}
+
 fn impl_json_api_deserialize(input: &DeriveInput) -> proc_macro2::TokenStream {
-    let input_receiver = InputReceiver::from_derive_input(input).unwrap();
+    let input_receiver = match InputReceiver::from_derive_input(input) {
+        Ok(input_receiver) => input_receiver,
+        Err(error) => return error.write_errors(),
+    };
     let struct_name = input_receiver.ident;
+
+    if let ast::Data::Struct(fields) = &input_receiver.data {
+        if fields.style == ast::Style::Tuple {
+            return impl_newtype(
+                &struct_name,
+                input_receiver.resource_type.as_deref(),
+                &input_receiver.validate,
+                fields,
+            );
+        }
+    }
+
     let resource_type = input_receiver
         .resource_type
         .unwrap_or_else(|| struct_name.to_string().to_snake_case());
 
     let mut field_initializers = proc_macro2::TokenStream::new();
     let mut fields = proc_macro2::TokenStream::new();
+    let mut attribute_descriptors = proc_macro2::TokenStream::new();
+    let mut relationship_descriptors = proc_macro2::TokenStream::new();
+    let mut errors = darling::Error::accumulator();
 
     input_receiver.data.map_struct_fields(|field| {
-        let field_name = match field.ident {
-            Some(field_name) => field_name,
+        let field_name = match &field.ident {
+            Some(field_name) => field_name.clone(),
             None => return,
         };
 
-        let json_field_name = match field.rename {
-            Some(rename) => rename,
+        let json_field_name = match &field.rename {
+            Some(rename) => rename.clone(),
             None => match input_receiver.rename_all {
                 RenameAll::CamelCase => field_name.to_string().to_lower_camel_case(),
                 RenameAll::PascalCase => field_name.to_string().to_pascal_case(),
@@ -190,19 +631,164 @@ fn impl_json_api_deserialize(input: &DeriveInput) -> proc_macro2::TokenStream {
             },
         };
 
-        let default = field.default;
+        let member_path = match &field.path {
+            Some(path)
+                if field.rename.is_some()
+                    || field.relationship.is_some()
+                    || field.links.is_some()
+                    || field.from_meta.is_some()
+                    || field.skip
+                    || field_name == "id" =>
+            {
+                errors.push(
+                    darling::Error::custom("`path` can only be used on attribute fields")
+                        .with_span(path),
+                );
+                return;
+            }
+            Some(path) => match parse_member_path(&path.value()) {
+                Some(segments) => segments,
+                None => {
+                    errors.push(
+                        darling::Error::custom("the attribute path contains an empty member name")
+                            .with_span(path),
+                    );
+                    return;
+                }
+            },
+            None => vec![json_field_name.clone()],
+        };
+        let json_field_name = member_path[0].clone();
+
+        let default_value = match &field.default {
+            Some(Override::Explicit(path)) => Some(quote! { #path() }),
+            Some(Override::Inherit) => Some(quote! { Default::default() }),
+            None => None,
+        };
+        let default = default_value.is_some();
         let optional = field.optional;
 
-        let field_tokens = match field.relationship {
+        if field.skip {
+            let value = default_value.unwrap_or_else(|| quote! { Default::default() });
+            fields.extend(quote! { #field_name: #value, });
+            return;
+        }
+
+        if let Some(key) = &field.from_meta {
+            let mut field_tokens = get_attribute_tokens(
+                &field_name,
+                "meta",
+                std::slice::from_ref(key),
+                &field_name.to_string(),
+                default_value.as_ref(),
+                optional,
+            );
+            field_tokens.extend(get_validation_tokens(
+                &field.validate,
+                &field_name,
+                field.ty.to_token_stream(),
+            ));
+
+            let pointer = format!("/meta{}", pointer_segment(key));
+            field_initializers.extend(get_scoped_tokens(&field_name, &pointer, field_tokens));
+            fields.extend(quote! { #field_name: #field_name.ok_or_else(Error::reported)?, });
+            return;
+        }
+
+        if let Some(links) = &field.links {
+            let pointer = match links {
+                Override::Inherit => "/links".to_string(),
+                Override::Explicit(relationship) => {
+                    format!("/relationships{}/links", pointer_segment(relationship))
+                }
+            };
+
+            let mut field_tokens = get_links_tokens(&field_name, links);
+            field_tokens.extend(get_validation_tokens(
+                &field.validate,
+                &field_name,
+                field.ty.to_token_stream(),
+            ));
+
+            field_initializers.extend(get_scoped_tokens(&field_name, &pointer, field_tokens));
+            fields.extend(quote! { #field_name: #field_name.ok_or_else(Error::reported)?, });
+            return;
+        }
+
+        let (relationship, resource) = match &field.relationship {
+            Some(relationship) => match resolve_relationship(&field, relationship) {
+                Ok((relationship, resource)) => (Some(relationship), resource),
+                Err(error) => {
+                    errors.push(error);
+                    return;
+                }
+            },
+            None => (None, None),
+        };
+
+        let field_string = field_name.to_string();
+
+        if let Some(relationship) = &relationship {
+            let cardinality = match relationship {
+                Relationship::Single => quote! { Single },
+                Relationship::Optional => quote! { Optional },
+                Relationship::Multiple => quote! { Multiple },
+            };
+            let resource = match &resource {
+                Some(resource) => quote! {
+                    <#resource as jsonapi_deserialize::JsonApiDeserialize>::DESCRIPTOR
+                },
+                None => quote! { None },
+            };
+
+            relationship_descriptors.extend(quote! {
+                jsonapi_deserialize::RelationshipDescriptor {
+                    field: #field_string,
+                    name: #json_field_name,
+                    cardinality: jsonapi_deserialize::Cardinality::#cardinality,
+                    resource: #resource,
+                    default: #default,
+                    optional: #optional,
+                },
+            });
+        } else if field_name != "id" {
+            attribute_descriptors.extend(quote! {
+                jsonapi_deserialize::AttributeDescriptor {
+                    field: #field_string,
+                    name: #json_field_name,
+                    default: #default,
+                    optional: #optional,
+                },
+            });
+        }
+
+        let pointer = if relationship.is_some() {
+            format!("/relationships{}", pointer_segment(&json_field_name))
+        } else if field_name == "id" {
+            "/id".to_string()
+        } else {
+            member_path.iter().fold("/attributes".to_string(), |pointer, name| {
+                pointer + &pointer_segment(name)
+            })
+        };
+
+        let mut field_tokens = match relationship {
             Some(Relationship::Single) => {
                 get_relationship_tokens(
                     &field_name,
                     &json_field_name,
                     "RawSingleRelationship",
-                    default,
+                    default_value.as_ref(),
                     optional,
-                    field.resource.map(|resource| quote! {
-                        let #field_name = included_map.get::<#resource>(&#field_name.kind, &#field_name.id)?;
+                    resource.map(|resource| {
+                        let annotation = default.then(|| {
+                            let ty = option_inner(&field.ty).unwrap_or(&field.ty);
+                            quote! { : #ty }
+                        });
+
+                        quote! {
+                            let #field_name #annotation = <_ as jsonapi_deserialize::FromIncluded<#resource>>::from_included(included_map, &#field_name)?;
+                        }
                     }),
                 )
             }
@@ -211,13 +797,18 @@ fn impl_json_api_deserialize(input: &DeriveInput) -> proc_macro2::TokenStream {
                     &field_name,
                     &json_field_name,
                     "RawOptionalRelationship",
-                    default,
+                    default_value.as_ref(),
                     optional,
-                    field.resource.map(|resource| quote! {
-                        let #field_name = match #field_name {
-                            Some(data) => Some(included_map.get::<#resource>(&data.kind, &data.id)?),
-                            None => None,
-                        };
+                    resource.map(|resource| {
+                        let ty = &field.ty;
+                        let annotation = default.then(|| quote! { : #ty });
+
+                        quote! {
+                            let #field_name #annotation = match #field_name {
+                                Some(data) => Some(<_ as jsonapi_deserialize::FromIncluded<#resource>>::from_included(included_map, &data)?),
+                                None => None,
+                            };
+                        }
                     }),
                 )
             }
@@ -226,14 +817,26 @@ fn impl_json_api_deserialize(input: &DeriveInput) -> proc_macro2::TokenStream {
                     &field_name,
                     &json_field_name,
                     "RawMultipleRelationship",
-                    default,
+                    default_value.as_ref(),
                     optional,
-                    field.resource.map(|resource| quote! {
-                        let #field_name = #field_name
-                            .into_iter()
-                            .map(|data| included_map.get::<#resource>(&data.kind, &data.id))
-                            .collect::<Result<_, _>>()?;
-                    }),
+                    {
+                        let annotation = default.then(|| {
+                            let ty = option_inner(&field.ty).unwrap_or(&field.ty);
+                            quote! { : #ty }
+                        });
+
+                        // References and resources are collected into whatever container the
+                        // field is typed as.
+                        Some(match &resource {
+                            Some(resource) => quote! {
+                                let #field_name #annotation = included_map
+                                    .resolve_many::<#resource, _, _>(&#field_name)?;
+                            },
+                            None => quote! {
+                                let #field_name #annotation = #field_name.into_iter().collect();
+                            },
+                        })
+                    },
                 )
             }
             None => {
@@ -247,45 +850,85 @@ fn impl_json_api_deserialize(input: &DeriveInput) -> proc_macro2::TokenStream {
                         )?;
                     }
                 } else {
-                    get_attribute_tokens(&field_name, &json_field_name, default, optional)
+                    let missing = match &field.path {
+                        Some(_) => member_path.join("."),
+                        None => field_string.clone(),
+                    };
+
+                    get_attribute_tokens(
+                        &field_name,
+                        "attributes",
+                        &member_path,
+                        &missing,
+                        default_value.as_ref(),
+                        optional,
+                    )
                 }
             }
         };
 
-        field_initializers.extend(field_tokens);
-        fields.extend(quote! { #field_name, });
+        field_tokens.extend(get_validation_tokens(
+            &field.validate,
+            &field_name,
+            field.ty.to_token_stream(),
+        ));
+
+        field_initializers.extend(get_scoped_tokens(&field_name, &pointer, field_tokens));
+        fields.extend(quote! { #field_name: #field_name.ok_or_else(Error::reported)?, });
     });
 
+    let validation_tokens = get_validation_tokens(
+        &input_receiver.validate,
+        &format_ident!("resource"),
+        quote! { Self },
+    );
+    let type_check_tokens = get_type_check_tokens(&resource_type);
+
+    if let Err(error) = errors.finish() {
+        return error.write_errors();
+    }
+
     quote! {
         impl jsonapi_deserialize::JsonApiDeserialize for #struct_name {
+            const DESCRIPTOR: Option<fn() -> &'static jsonapi_deserialize::ResourceDescriptor> =
+                Some(<Self as jsonapi_deserialize::JsonApiResource>::descriptor);
+
             fn from_value(
                 value: &serde_json::Value,
                 included_map: &mut jsonapi_deserialize::IncludedMap,
             ) -> Result<Self, jsonapi_deserialize::DeserializeError> {
                 use jsonapi_deserialize::DeserializeError as Error;
 
-                let data = value.as_object().ok_or(Error::InvalidType("Expected an object"))?;
+                // Shares resources behind `Rc` between all relationships of the resource.
+                included_map.sharing(|included_map| -> Result<Self, Error> {
+                    #type_check_tokens
 
-                let resource_type: String = serde_json::from_value(
-                    data
-                        .get("type")
-                        .ok_or_else(|| Error::MissingResourceType)?
-                        .clone(),
-                )?;
+                    #field_initializers
 
-                if resource_type != #resource_type {
-                    return Err(Error::ResourceTypeMismatch {
-                        expected: #resource_type.to_string(),
-                        found: resource_type,
-                    });
-                }
+                    let resource = Self {
+                        #fields
+                    };
 
-                #field_initializers
+                    #validation_tokens
 
-                Ok(Self {
-                    #fields
+                    Ok(resource)
                 })
             }
         }
+
+        impl jsonapi_deserialize::JsonApiResource for #struct_name {
+            const TYPE: &'static str = #resource_type;
+
+            fn descriptor() -> &'static jsonapi_deserialize::ResourceDescriptor {
+                static DESCRIPTOR: jsonapi_deserialize::ResourceDescriptor =
+                    jsonapi_deserialize::ResourceDescriptor {
+                        kind: #resource_type,
+                        attributes: &[#attribute_descriptors],
+                        relationships: &[#relationship_descriptors],
+                    };
+
+                &DESCRIPTOR
+            }
+        }
     }
 }
@@ -0,0 +1,259 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, deserialize_document_with_options, DeserializeError, DeserializeOptions,
+    Document, Error, IncludedMap, JsonApiDeserialize,
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+    view_count: u32,
+    #[json_api(relationship = "single", resource = "Person")]
+    author: Arc<Person>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+const DOCUMENT: &str = r#"{
+    "data": [
+        {
+            "id": "1",
+            "type": "article",
+            "attributes": {
+                "viewCount": "many"
+            },
+            "relationships": {
+                "author": {
+                    "data": { "type": "person", "id": "p-1" }
+                }
+            }
+        },
+        {
+            "id": "2",
+            "type": "article",
+            "attributes": {
+                "title": "Foo",
+                "viewCount": 1
+            },
+            "relationships": {
+                "author": {
+                    "data": { "type": "person", "id": "p-2" }
+                }
+            }
+        },
+        {
+            "id": "3",
+            "type": "article",
+            "attributes": {
+                "title": "Bar",
+                "viewCount": 2
+            },
+            "relationships": {
+                "author": {
+                    "data": { "type": "person", "id": "p-1" }
+                }
+            }
+        },
+        {
+            "id": "4",
+            "type": "comment"
+        }
+    ],
+    "included": [
+        {
+            "id": "p-1",
+            "type": "person",
+            "attributes": {
+                "name": 42
+            }
+        }
+    ]
+}"#;
+
+fn accumulate() -> DeserializeOptions {
+    DeserializeOptions {
+        accumulate_errors: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_fail_fast_by_default() {
+    let result: Result<Document<Vec<Article>>, Error> = deserialize_document(DOCUMENT);
+
+    assert!(matches!(
+        result,
+        Err(Error::DeserializeError(DeserializeError::MissingField(
+            "title"
+        )))
+    ));
+}
+
+#[test]
+fn test_accumulate_errors() {
+    let result: Result<Document<Vec<Article>>, Error> =
+        deserialize_document_with_options(DOCUMENT, &accumulate());
+
+    let errors = match result {
+        Err(Error::DeserializeError(DeserializeError::Multiple(errors))) => errors,
+        result => panic!("Expected multiple errors, but got {:?}", result),
+    };
+
+    let pointers: Vec<_> = errors.iter().map(|error| error.pointer.as_str()).collect();
+    assert_eq!(
+        pointers,
+        [
+            "/data/0/attributes/title",
+            "/data/0/attributes/viewCount",
+            "/included/0/attributes/name",
+            "/data/1/relationships/author",
+            "/data/3",
+        ]
+    );
+
+    assert!(matches!(
+        errors[0].error,
+        DeserializeError::MissingField("title")
+    ));
+    assert!(matches!(errors[1].error, DeserializeError::SerdeError(_)));
+    assert!(matches!(errors[2].error, DeserializeError::SerdeError(_)));
+    assert!(matches!(
+        errors[3].error,
+        DeserializeError::MissingResource { .. }
+    ));
+    assert!(matches!(
+        errors[4].error,
+        DeserializeError::ResourceTypeMismatch { .. }
+    ));
+}
+
+#[test]
+fn test_accumulate_without_errors() {
+    let document: Document<Article> = deserialize_document_with_options(
+        r#"{
+            "data": {
+                "id": "1",
+                "type": "article",
+                "attributes": {
+                    "title": "Foo",
+                    "viewCount": 1
+                },
+                "relationships": {
+                    "author": {
+                        "data": { "type": "person", "id": "p-1" }
+                    }
+                }
+            },
+            "included": [
+                {
+                    "id": "p-1",
+                    "type": "person",
+                    "attributes": {
+                        "name": "John Smith"
+                    }
+                }
+            ]
+        }"#,
+        &accumulate(),
+    )
+    .unwrap();
+
+    assert_eq!(document.data.author.name, "John Smith");
+}
+
+#[derive(Debug)]
+struct Unrecorded;
+
+impl JsonApiDeserialize for Unrecorded {
+    fn from_value(_: &Value, _: &mut IncludedMap) -> Result<Self, DeserializeError> {
+        Err(DeserializeError::Multiple(Vec::new()))
+    }
+}
+
+#[test]
+fn test_empty_multiple_error_is_recorded() {
+    let result: Result<Document<Unrecorded>, Error> =
+        deserialize_document_with_options(r#"{ "data": null }"#, &accumulate());
+
+    let errors = match result {
+        Err(Error::DeserializeError(DeserializeError::Multiple(errors))) => errors,
+        result => panic!("Expected multiple errors, but got {:?}", result),
+    };
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].pointer, "/data");
+    assert!(matches!(&errors[0].error, DeserializeError::Multiple(errors) if errors.is_empty()));
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Post {
+    id: String,
+    #[json_api(relationship = "multiple", resource = "Tag")]
+    tags: Vec<Arc<Tag>>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Tag {
+    name: String,
+}
+
+#[test]
+fn test_accumulate_to_many_and_unreferenced_errors() {
+    let result: Result<Document<Post>, Error> = deserialize_document_with_options(
+        r#"{
+            "data": {
+                "id": "1",
+                "type": "post",
+                "relationships": {
+                    "tags": {
+                        "data": [
+                            { "type": "tag", "id": "q" },
+                            { "type": "tag", "id": "r" },
+                            { "type": "tag", "id": "p" }
+                        ]
+                    }
+                }
+            },
+            "included": [
+                { "id": "p", "type": "tag", "attributes": { "name": 1 } },
+                { "id": "s", "type": "tag", "attributes": { "name": 2 } },
+                { "id": "c", "type": "comment" }
+            ]
+        }"#,
+        &accumulate(),
+    );
+
+    let errors = match result {
+        Err(Error::DeserializeError(DeserializeError::Multiple(errors))) => errors,
+        result => panic!("Expected multiple errors, but got {:?}", result),
+    };
+
+    let pointers: Vec<_> = errors.iter().map(|error| error.pointer.as_str()).collect();
+    assert_eq!(
+        pointers,
+        [
+            "/data/relationships/tags/data/0",
+            "/data/relationships/tags/data/1",
+            "/included/0/attributes/name",
+            "/included/1/attributes/name",
+        ]
+    );
+
+    assert!(matches!(
+        &errors[0].error,
+        DeserializeError::MissingResource { id, .. } if id == "q"
+    ));
+    assert!(matches!(
+        &errors[1].error,
+        DeserializeError::MissingResource { id, .. } if id == "r"
+    ));
+    assert!(matches!(errors[2].error, DeserializeError::SerdeError(_)));
+    assert!(matches!(errors[3].error, DeserializeError::SerdeError(_)));
+}
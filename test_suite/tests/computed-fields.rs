@@ -0,0 +1,106 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, DeserializeError, Document, Error, JsonApiDeserialize, JsonApiResource,
+};
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+    #[json_api(skip)]
+    selected: bool,
+    #[json_api(skip, default = "default_word_count")]
+    word_count: usize,
+    #[json_api(default = "default_language")]
+    language: String,
+    #[json_api(from_meta = "revision")]
+    revision: u32,
+    #[json_api(from_meta = "last-editor", optional)]
+    last_editor: Option<String>,
+    #[json_api(relationship, default = "default_tags")]
+    tags: Vec<Arc<Tag>>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Tag {
+    #[json_api(from_meta = "score", default)]
+    score: u32,
+}
+
+fn default_word_count() -> usize {
+    100
+}
+
+fn default_language() -> String {
+    "en".to_string()
+}
+
+fn default_tags() -> Vec<Arc<Tag>> {
+    vec![Arc::new(Tag { score: 0 })]
+}
+
+#[test]
+fn test_skipped_and_default_fields() {
+    let document: Document<Article> = deserialize_document(
+        r#"{
+            "data": {
+                "type": "article",
+                "id": "1",
+                "attributes": { "title": "Foo", "selected": true },
+                "meta": { "revision": 3 }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    assert!(!document.data.selected);
+    assert_eq!(document.data.word_count, 100);
+    assert_eq!(document.data.language, "en");
+    assert_eq!(document.data.tags.len(), 1);
+}
+
+#[test]
+fn test_fields_from_meta() {
+    let document: Document<Article> = deserialize_document(
+        r#"{
+            "data": {
+                "type": "article",
+                "id": "1",
+                "attributes": { "title": "Foo", "language": "de" },
+                "relationships": { "tags": { "data": [{ "type": "tag", "id": "t-1" }] } },
+                "meta": { "revision": 3, "last-editor": "John Smith" }
+            },
+            "included": [{ "type": "tag", "id": "t-1", "meta": { "score": 5 } }]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(document.data.language, "de");
+    assert_eq!(document.data.revision, 3);
+    assert_eq!(document.data.last_editor.as_deref(), Some("John Smith"));
+    assert_eq!(document.data.tags[0].score, 5);
+}
+
+#[test]
+fn test_missing_meta_field() {
+    let error = deserialize_document::<Article>(
+        r#"{ "data": { "type": "article", "id": "1", "attributes": { "title": "Foo" } } }"#,
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::DeserializeError(DeserializeError::MissingField("revision"))
+    ));
+}
+
+#[test]
+fn test_descriptor_excludes_computed_fields() {
+    let descriptor = Article::descriptor();
+
+    assert!(descriptor.attribute("title").is_some());
+    assert!(descriptor.attribute("selected").is_none());
+    assert!(descriptor.attribute("revision").is_none());
+}
@@ -0,0 +1,154 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, deserialize_document_with_options, DeserializeError, DeserializeOptions,
+    Document, DuplicatePolicy, Error, JsonApiDeserialize, Warning,
+};
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    #[json_api(relationship)]
+    author: Arc<Person>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+    #[json_api(default)]
+    email: Option<String>,
+    #[json_api(default)]
+    address: Option<Address>,
+}
+
+#[derive(Debug, serde::Deserialize, PartialEq)]
+struct Address {
+    city: String,
+    #[serde(default)]
+    street: Option<String>,
+}
+
+const DOCUMENT: &str = r#"{
+    "data": [
+        {
+            "type": "article",
+            "id": "1",
+            "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+        },
+        {
+            "type": "article",
+            "id": "1",
+            "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+        }
+    ],
+    "included": [
+        {
+            "type": "person",
+            "id": "p-1",
+            "attributes": {
+                "name": "John Smith",
+                "email": "john@example.com",
+                "address": { "city": "Berlin", "street": "Hauptstraße" }
+            }
+        },
+        {
+            "type": "person",
+            "id": "p-1",
+            "attributes": { "name": "Johnny Smith", "address": { "city": "Hamburg" } }
+        }
+    ]
+}"#;
+
+fn deserialize(policy: DuplicatePolicy) -> Result<Document<Vec<Article>>, Error> {
+    let options = DeserializeOptions {
+        duplicates: policy,
+        ..Default::default()
+    };
+
+    deserialize_document_with_options(DOCUMENT, &options)
+}
+
+#[test]
+fn test_duplicate_warnings() {
+    let document: Document<Vec<Article>> = deserialize_document(DOCUMENT).unwrap();
+
+    assert_eq!(
+        document.warnings,
+        vec![
+            Warning::DuplicateResource {
+                kind: "article".to_string(),
+                id: "1".to_string(),
+                pointer: "/data/1".to_string(),
+            },
+            Warning::DuplicateResource {
+                kind: "person".to_string(),
+                id: "p-1".to_string(),
+                pointer: "/included/1".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_last_wins() {
+    let document = deserialize(DuplicatePolicy::LastWins).unwrap();
+    let author = &document.data[0].author;
+
+    assert_eq!(author.name, "Johnny Smith");
+    assert!(author.email.is_none());
+}
+
+#[test]
+fn test_first_wins() {
+    let document = deserialize(DuplicatePolicy::FirstWins).unwrap();
+
+    assert_eq!(document.data[0].author.name, "John Smith");
+    assert_eq!(document.warnings.len(), 2);
+}
+
+#[test]
+fn test_deep_merge() {
+    let document = deserialize(DuplicatePolicy::DeepMerge).unwrap();
+    let author = &document.data[0].author;
+
+    assert_eq!(author.name, "Johnny Smith");
+    assert_eq!(author.email.as_deref(), Some("john@example.com"));
+    assert_eq!(
+        author.address,
+        Some(Address {
+            city: "Hamburg".to_string(),
+            street: Some("Hauptstraße".to_string()),
+        })
+    );
+}
+
+#[test]
+fn test_error() {
+    let error = deserialize(DuplicatePolicy::Error).unwrap_err();
+
+    match error {
+        Error::DeserializeError(DeserializeError::DuplicateResource { kind, id }) => {
+            assert_eq!(kind, "article");
+            assert_eq!(id, "1");
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_no_duplicates() {
+    let document: Document<Article> = deserialize_document(
+        r#"{
+            "data": {
+                "type": "article",
+                "id": "1",
+                "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+            },
+            "included": [{ "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } }]
+        }"#,
+    )
+    .unwrap();
+
+    assert!(document.warnings.is_empty());
+}
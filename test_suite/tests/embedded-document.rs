@@ -0,0 +1,89 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{Document, JsonApiDeserialize, JsonApiDocument};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+    #[json_api(relationship)]
+    author: Arc<Person>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Webhook {
+    event: String,
+    #[serde(with = "jsonapi_deserialize::serde_document")]
+    payload: Document<Article>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    id: u32,
+    body: JsonApiDocument<Vec<Article>>,
+}
+
+const PAYLOAD: &str = r#"{
+    "data": {
+        "type": "article",
+        "id": "1",
+        "attributes": { "title": "Foo" },
+        "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+    },
+    "included": [
+        { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } }
+    ]
+}"#;
+
+#[test]
+fn test_serde_with() {
+    let webhook: Webhook = serde_json::from_str(&format!(
+        r#"{{ "event": "created", "payload": {PAYLOAD} }}"#
+    ))
+    .unwrap();
+
+    assert_eq!(webhook.event, "created");
+    assert_eq!(webhook.payload.data.title, "Foo");
+    assert_eq!(webhook.payload.data.author.name, "John Smith");
+}
+
+#[test]
+fn test_newtype() {
+    let message: Message = serde_json::from_str(
+        r#"{
+            "id": 7,
+            "body": {
+                "data": [{
+                    "type": "article",
+                    "id": "1",
+                    "attributes": { "title": "Foo" },
+                    "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+                }],
+                "included": [
+                    { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } }
+                ]
+            }
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(message.body.data[0].author.name, "John Smith");
+    assert_eq!(message.body.into_inner().data.len(), 1);
+}
+
+#[test]
+fn test_embedded_errors() {
+    let error = serde_json::from_str::<Webhook>(
+        r#"{ "event": "created", "payload": { "data": { "type": "comment", "id": "1" } } }"#,
+    )
+    .unwrap_err();
+
+    assert!(error.to_string().starts_with("Resource type mismatch"));
+}
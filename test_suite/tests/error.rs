@@ -17,11 +17,11 @@ fn test_document_errors() {
         }"#,
     );
 
-    let errors = if let Err(Error::DocumentError(errors)) = result {
+    let errors = if let Err(Error::DocumentError(errors, None)) = result {
         errors
     } else {
         panic!("Expected DocumentError, but got {:?}", result);
     };
 
-    assert_eq!(errors.get(0).unwrap().status, Some("404".to_string()));
+    assert_eq!(errors.first().unwrap().status, Some("404".to_string()));
 }
@@ -0,0 +1,82 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, Cardinality, Document, JsonApiDeserialize, JsonApiResource, Reference,
+};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    #[json_api(relationship)]
+    author: Arc<Person>,
+    #[json_api(relationship)]
+    reviewer: Option<Rc<Person>>,
+    #[json_api(relationship)]
+    editors: BTreeMap<String, Arc<Person>>,
+    #[json_api(relationship)]
+    tags: Vec<Reference>,
+    #[json_api(relationship, optional)]
+    publisher: Option<Reference>,
+    #[json_api(relationship = "multiple")]
+    contributors: Vec<Arc<Person>>,
+}
+
+#[derive(Clone, Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+#[test]
+fn test_inferred_relationships() {
+    let document: Document<Article> = deserialize_document(
+        r#"{
+            "data": {
+                "id": "1",
+                "type": "article",
+                "relationships": {
+                    "author": { "data": { "type": "person", "id": "p-1" } },
+                    "reviewer": { "data": null },
+                    "editors": { "data": [{ "type": "person", "id": "p-1" }] },
+                    "tags": { "data": [{ "type": "tag", "id": "t-1" }] },
+                    "contributors": { "data": [] }
+                }
+            },
+            "included": [
+                { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(document.data.author.name, "John Smith");
+    assert!(document.data.reviewer.is_none());
+    assert!(Arc::ptr_eq(
+        &document.data.author,
+        &document.data.editors["p-1"]
+    ));
+    assert_eq!(document.data.tags[0].id, "t-1");
+    assert!(document.data.publisher.is_none());
+}
+
+#[test]
+fn test_inferred_descriptors() {
+    let descriptor = Article::descriptor();
+
+    let author = descriptor.relationship("author").unwrap();
+    assert_eq!(author.cardinality, Cardinality::Single);
+    assert_eq!(author.resource.unwrap()().kind, "person");
+
+    let reviewer = descriptor.relationship("reviewer").unwrap();
+    assert_eq!(reviewer.cardinality, Cardinality::Optional);
+
+    let editors = descriptor.relationship("editors").unwrap();
+    assert_eq!(editors.cardinality, Cardinality::Multiple);
+    assert!(editors.resource.is_some());
+
+    let tags = descriptor.relationship("tags").unwrap();
+    assert_eq!(tags.cardinality, Cardinality::Multiple);
+    assert!(tags.resource.is_none());
+}
@@ -0,0 +1,75 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{deserialize_document, Document, Reference};
+use serde_json::json;
+
+#[test]
+fn test_to_many_linkage() {
+    let document: Document<Vec<Reference>> = deserialize_document(
+        r#"{
+            "links": {
+                "self": "/articles/1/relationships/tags",
+                "related": "/articles/1/tags"
+            },
+            "data": [
+                { "type": "tags", "id": "2" },
+                { "type": "tags", "id": "3", "meta": { "weight": 0.5 } }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        document.data,
+        vec![Reference::new("tags", "2"), Reference::new("tags", "3")]
+    );
+    assert!(document.data[0].meta.is_none());
+    assert_eq!(
+        document.data[1].meta.as_ref().unwrap().get("weight"),
+        Some(&json!(0.5))
+    );
+    assert!(document.links.unwrap().related.is_some());
+}
+
+#[test]
+fn test_to_one_linkage() {
+    let document: Document<Option<Reference>> = deserialize_document(
+        r#"{
+            "data": { "type": "people", "id": "12" }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(document.data, Some(Reference::new("people", "12")));
+
+    let document: Document<Option<Reference>> =
+        deserialize_document(r#"{ "data": null }"#).unwrap();
+    assert!(document.data.is_none());
+}
+
+#[test]
+fn test_modification_body() {
+    let mut reference = Reference::new("comments", "12");
+    let body = json!({ "data": [reference.clone()] });
+    assert_eq!(
+        body,
+        json!({ "data": [{ "type": "comments", "id": "12" }] })
+    );
+
+    let document: Document<Vec<Reference>> = deserialize_document(&body.to_string()).unwrap();
+    assert_eq!(document.data, vec![reference.clone()]);
+
+    reference.meta = Some([("reason".to_string(), json!("spam"))].into());
+    assert_eq!(
+        json!({ "data": reference }),
+        json!({ "data": { "type": "comments", "id": "12", "meta": { "reason": "spam" } } })
+    );
+}
+
+#[test]
+fn test_meta_is_ignored_for_equality() {
+    let mut reference = Reference::new("comments", "12");
+    reference.meta = Some([("reason".to_string(), json!("spam"))].into());
+
+    assert_eq!(reference, Reference::new("comments", "12"));
+    assert_ne!(reference, Reference::new("comments", "13"));
+}
@@ -0,0 +1,79 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{deserialize_document, Document, JsonApiDeserialize, Linked};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Team {
+    id: String,
+    #[json_api(relationship)]
+    members: Vec<Linked<Arc<Person>, Membership>>,
+    #[json_api(relationship)]
+    lead: Option<Linked<Arc<Person>, Option<Membership>>>,
+    #[json_api(relationship = "single", resource = "Person")]
+    founder: Linked<Person>,
+}
+
+#[derive(Clone, Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Membership {
+    role: String,
+}
+
+const DOCUMENT: &str = r#"{
+    "data": {
+        "id": "1",
+        "type": "team",
+        "relationships": {
+            "members": {
+                "data": [
+                    { "type": "person", "id": "p-1", "meta": { "role": "owner" } },
+                    { "type": "person", "id": "p-2", "meta": { "role": "member" } }
+                ]
+            },
+            "lead": { "data": { "type": "person", "id": "p-1" } },
+            "founder": { "data": { "type": "person", "id": "p-2", "meta": { "since": 2020 } } }
+        }
+    },
+    "included": [
+        { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } },
+        { "type": "person", "id": "p-2", "attributes": { "name": "Jane Doe" } }
+    ]
+}"#;
+
+#[test]
+fn test_identifier_meta() {
+    let document: Document<Team> = deserialize_document(DOCUMENT).unwrap();
+    let members = &document.data.members;
+
+    assert_eq!(members[0].name, "John Smith");
+    assert_eq!(members[0].meta.role, "owner");
+    assert_eq!(members[1].resource.name, "Jane Doe");
+    assert_eq!(members[1].meta.role, "member");
+}
+
+#[test]
+fn test_absent_identifier_meta() {
+    let document: Document<Team> = deserialize_document(DOCUMENT).unwrap();
+    let lead = document.data.lead.unwrap();
+
+    assert!(Arc::ptr_eq(
+        &lead.resource,
+        &document.data.members[0].resource
+    ));
+    assert!(lead.meta.is_none());
+}
+
+#[test]
+fn test_untyped_identifier_meta() {
+    let document: Document<Team> = deserialize_document(DOCUMENT).unwrap();
+
+    assert_eq!(document.data.founder.name, "Jane Doe");
+    assert_eq!(document.data.founder.meta.get("since"), Some(&json!(2020)));
+}
@@ -0,0 +1,274 @@
+#![allow(unused)]
+
+use http::Uri;
+use jsonapi_deserialize::{
+    deserialize_document, deserialize_document_with_options, DeserializeOptions, Document, Error,
+    JsonApiDeserialize, RelationshipLinks, ResourceLinks,
+};
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+#[json_api(resource_type = "article")]
+struct LinkedArticle {
+    id: String,
+    #[json_api(links)]
+    links: ResourceLinks,
+    #[json_api(links = "author")]
+    author_links: Option<RelationshipLinks>,
+    #[json_api(links = "comments")]
+    comments_links: Option<RelationshipLinks>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+#[json_api(resource_type = "article")]
+struct AuthoredArticle {
+    id: String,
+    #[json_api(links)]
+    links: ResourceLinks,
+    #[json_api(links = "author")]
+    author_links: RelationshipLinks,
+    #[json_api(relationship)]
+    author: Arc<Person>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    #[json_api(links)]
+    links: ResourceLinks,
+}
+
+#[test]
+fn test_document_with_base_uri() {
+    let document: Document<Article> = deserialize_document(
+        r#"{
+            "data": {
+                "id": "1",
+                "type": "article"
+            },
+            "links": {
+                "self": "/articles/1",
+                "next": { "href": "/articles?page=2" },
+                "related": "https://example.org/people/1",
+                "prev": null
+            }
+        }"#,
+    )
+    .unwrap();
+
+    assert!(document.links.as_ref().unwrap().prev.is_none());
+
+    let base: Uri = "https://example.com/api/articles".parse().unwrap();
+    let links = document.with_base_uri(&base).links.unwrap();
+
+    assert_eq!(
+        links.this.unwrap().href,
+        "https://example.com/articles/1".parse::<Uri>().unwrap()
+    );
+    assert_eq!(
+        links.next.unwrap().href,
+        "https://example.com/articles?page=2"
+            .parse::<Uri>()
+            .unwrap()
+    );
+    assert_eq!(
+        links.related.unwrap().href,
+        "https://example.org/people/1".parse::<Uri>().unwrap()
+    );
+}
+
+#[test]
+fn test_error_with_base_uri() {
+    let result: Result<Document<Article>, Error> = deserialize_document(
+        r#"{
+            "errors": [{
+                "status": "404",
+                "links": {
+                    "about": "/errors/not-found"
+                }
+            }]
+        }"#,
+    );
+
+    let base: Uri = "https://example.com/api/articles/1".parse().unwrap();
+    let errors = match result.map_err(|error| error.with_base_uri(&base)) {
+        Err(Error::DocumentError(errors, _)) => errors,
+        result => panic!("Expected DocumentError, but got {:?}", result),
+    };
+
+    assert_eq!(
+        errors[0]
+            .links
+            .as_ref()
+            .unwrap()
+            .about
+            .as_ref()
+            .unwrap()
+            .href,
+        "https://example.com/errors/not-found"
+            .parse::<Uri>()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_document_links_map() {
+    let document: Document<Article> = deserialize_document(
+        r#"{
+            "data": {
+                "id": "1",
+                "type": "article"
+            },
+            "links": {
+                "self": "/articles/1",
+                "profile": ["https://example.com/profiles/a", { "href": "/profiles/b" }],
+                "export": "/articles/1/export",
+                "custom": null,
+                "alternate": "/articles/1.html"
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let links = document.links.unwrap();
+
+    assert_eq!(links.get("self").unwrap().href, "/articles/1");
+    assert_eq!(links.get("export").unwrap().href, "/articles/1/export");
+    assert!(links.get("custom").is_none());
+    assert!(links.get("next").is_none());
+    assert_eq!(
+        links.other.keys().collect::<Vec<_>>(),
+        ["export", "custom", "alternate"]
+    );
+
+    let profiles: Vec<_> = links
+        .profile
+        .iter()
+        .map(|link| link.href.to_string())
+        .collect();
+    assert_eq!(profiles, ["https://example.com/profiles/a", "/profiles/b"]);
+}
+
+#[test]
+fn test_resource_and_relationship_links() {
+    let document: Document<LinkedArticle> = deserialize_document(
+        r#"{
+            "data": {
+                "id": "1",
+                "type": "article",
+                "links": {
+                    "self": "/articles/1",
+                    "edit": "/articles/1/edit"
+                },
+                "relationships": {
+                    "author": {
+                        "links": {
+                            "self": "/articles/1/relationships/author",
+                            "related": "/articles/1/author"
+                        }
+                    }
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let base: Uri = "https://example.com/".parse().unwrap();
+    let links = document.data.links.with_base_uri(&base);
+    assert_eq!(
+        links.get("edit").unwrap().href,
+        "https://example.com/articles/1/edit"
+    );
+
+    let author_links = document.data.author_links.unwrap();
+    assert_eq!(
+        author_links.get("related").unwrap().href,
+        "/articles/1/author"
+    );
+    assert!(document.data.comments_links.is_none());
+}
+
+#[test]
+fn test_resolve_links_while_deserializing() {
+    let options = DeserializeOptions {
+        base_uri: Some("https://example.com/api/articles".parse().unwrap()),
+        ..Default::default()
+    };
+    let document: Document<Vec<AuthoredArticle>> = deserialize_document_with_options(
+        r#"{
+            "data": [{
+                "id": "1",
+                "type": "article",
+                "links": { "self": "articles/1" },
+                "relationships": {
+                    "author": {
+                        "data": { "type": "person", "id": "9" },
+                        "links": { "related": { "href": "articles/1/author" } }
+                    }
+                }
+            }],
+            "included": [{
+                "id": "9",
+                "type": "person",
+                "links": { "self": "../people/9", "profile": "https://example.org/9" }
+            }],
+            "links": { "next": "?page=2" }
+        }"#,
+        &options,
+    )
+    .unwrap();
+
+    let article = &document.data[0];
+    assert_eq!(
+        article.links.get("self").unwrap().href,
+        "https://example.com/api/articles/1"
+    );
+    assert_eq!(
+        article.author_links.get("related").unwrap().href,
+        "https://example.com/api/articles/1/author"
+    );
+    assert_eq!(
+        article.author.links.get("self").unwrap().href,
+        "https://example.com/people/9"
+    );
+    assert_eq!(
+        article.author.links.get("profile").unwrap().href,
+        "https://example.org/9"
+    );
+    assert_eq!(
+        document.links.unwrap().next.unwrap().href,
+        "https://example.com/api/articles?page=2"
+    );
+}
+
+#[test]
+fn test_resolve_error_links_while_deserializing() {
+    let options = DeserializeOptions {
+        base_uri: Some("https://example.com/api/articles/1".parse().unwrap()),
+        ..Default::default()
+    };
+    let result: Result<Document<Article>, Error> = deserialize_document_with_options(
+        r#"{ "errors": [{ "links": { "about": "/errors/not-found" } }] }"#,
+        &options,
+    );
+
+    let errors = match result {
+        Err(Error::DocumentError(errors, _)) => errors,
+        result => panic!("Expected DocumentError, but got {:?}", result),
+    };
+    assert_eq!(
+        errors[0]
+            .links
+            .as_ref()
+            .unwrap()
+            .about
+            .as_ref()
+            .unwrap()
+            .href,
+        "https://example.com/errors/not-found"
+    );
+}
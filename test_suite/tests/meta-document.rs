@@ -0,0 +1,102 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, deserialize_document_iter, deserialize_meta_document, DeserializeError,
+    Document, Error, JsonApiDeserialize,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Job {
+    status: String,
+}
+
+const META_DOCUMENT: &str = r#"{ "meta": { "status": "pending" } }"#;
+
+#[test]
+fn test_meta_only_document() {
+    let document: Document<()> = deserialize_document(META_DOCUMENT).unwrap();
+
+    assert_eq!(document.meta.unwrap()["status"], json!("pending"));
+}
+
+#[test]
+fn test_typed_meta_only_document() {
+    let job: Job = deserialize_meta_document(META_DOCUMENT).unwrap();
+
+    assert_eq!(
+        job,
+        Job {
+            status: "pending".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_meta_only_document_with_resource() {
+    let error = deserialize_document::<Article>(META_DOCUMENT).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::DeserializeError(DeserializeError::IncompleteDocument)
+    ));
+
+    let error = deserialize_document::<Vec<Article>>(META_DOCUMENT).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::DeserializeError(DeserializeError::IncompleteDocument)
+    ));
+
+    let error = deserialize_document::<Option<Article>>(META_DOCUMENT).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::DeserializeError(DeserializeError::IncompleteDocument)
+    ));
+}
+
+#[test]
+fn test_meta_only_document_iter() {
+    let mut iter = deserialize_document_iter::<Article, _>(META_DOCUMENT.as_bytes());
+
+    assert!(iter.next().is_none());
+    assert_eq!(iter.meta().unwrap()["status"], json!("pending"));
+}
+
+#[test]
+fn test_empty_document() {
+    let error = deserialize_document::<()>("{}").unwrap_err();
+    assert!(matches!(
+        error,
+        Error::DeserializeError(DeserializeError::IncompleteDocument)
+    ));
+
+    let error = deserialize_meta_document::<Job>(r#"{ "data": null }"#).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::DeserializeError(DeserializeError::IncompleteDocument)
+    ));
+}
+
+#[test]
+fn test_null_primary_data() {
+    let document: Document<Option<Article>> = deserialize_document(r#"{ "data": null }"#).unwrap();
+    assert!(document.data.is_none());
+
+    let document: Document<Option<Article>> = deserialize_document(
+        r#"{ "data": { "type": "article", "id": "1", "attributes": { "title": "Foo" } } }"#,
+    )
+    .unwrap();
+    assert_eq!(document.data.unwrap().title, "Foo");
+
+    let error = deserialize_document::<Article>(r#"{ "data": null }"#).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::DeserializeError(DeserializeError::InvalidType(_))
+    ));
+}
@@ -0,0 +1,124 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, deserialize_document_with_options, DeserializeError, DeserializeOptions,
+    Document, Error, JsonApiDeserialize, JsonApiResource,
+};
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Venue {
+    id: String,
+    name: String,
+    #[json_api(path = "address.geo.lat")]
+    latitude: f64,
+    #[json_api(path = "/address/geo/lng")]
+    longitude: f64,
+    #[json_api(path = "address.city", optional)]
+    city: Option<String>,
+    #[json_api(path = "address.zip~code/extra", default)]
+    zip_code: String,
+}
+
+#[test]
+fn test_nested_attributes() {
+    let document: Document<Venue> = deserialize_document(
+        r#"{
+            "data": {
+                "type": "venue",
+                "id": "1",
+                "attributes": {
+                    "name": "Town Hall",
+                    "address": {
+                        "city": "Berlin",
+                        "geo": { "lat": 52.52, "lng": 13.40 },
+                        "zip~code/extra": "10178"
+                    }
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(document.data.latitude, 52.52);
+    assert_eq!(document.data.longitude, 13.40);
+    assert_eq!(document.data.city.as_deref(), Some("Berlin"));
+    assert_eq!(document.data.zip_code, "10178");
+}
+
+#[test]
+fn test_missing_intermediate_member() {
+    let document: Document<Venue> = deserialize_document(
+        r#"{
+            "data": {
+                "type": "venue",
+                "id": "1",
+                "attributes": { "name": "Town Hall", "address": { "geo": { "lat": 1, "lng": 2 } } }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(document.data.city, None);
+    assert_eq!(document.data.zip_code, "");
+}
+
+#[test]
+fn test_missing_nested_attribute() {
+    let error = deserialize_document::<Venue>(
+        r#"{
+            "data": {
+                "type": "venue",
+                "id": "1",
+                "attributes": { "name": "Town Hall", "address": null }
+            }
+        }"#,
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::DeserializeError(DeserializeError::MissingField("address.geo.lat"))
+    ));
+}
+
+#[test]
+fn test_nested_attribute_pointer() {
+    let result: Result<Document<Venue>, _> = deserialize_document_with_options(
+        r#"{
+            "data": {
+                "type": "venue",
+                "id": "1",
+                "attributes": { "name": "Town Hall", "address": { "geo": { "lat": "north" } } }
+            }
+        }"#,
+        &DeserializeOptions {
+            accumulate_errors: true,
+            ..Default::default()
+        },
+    );
+
+    let errors = match result {
+        Err(Error::DeserializeError(DeserializeError::Multiple(errors))) => errors,
+        result => panic!("Expected multiple errors, but got {:?}", result),
+    };
+
+    let pointers: Vec<_> = errors.iter().map(|error| error.pointer.as_str()).collect();
+    assert_eq!(
+        pointers,
+        [
+            "/data/attributes/address/geo/lat",
+            "/data/attributes/address/geo/lng",
+        ]
+    );
+}
+
+#[test]
+fn test_descriptor_names_top_level_member() {
+    let descriptor = Venue::descriptor();
+
+    assert_eq!(descriptor.attribute("address").unwrap().field, "latitude");
+    assert_eq!(
+        descriptor.field_pointer("longitude").as_deref(),
+        Some("/attributes/address")
+    );
+}
@@ -0,0 +1,139 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, DeserializeError, Document, Error, IncludedMap, JsonApiDeserialize,
+    JsonApiResource,
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct User {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct AdminUser(User);
+
+#[derive(Debug, JsonApiDeserialize)]
+#[json_api(resource_type = "admins")]
+struct Admin(User);
+
+#[derive(Debug, JsonApiDeserialize)]
+#[json_api(validate = "validate_named")]
+struct NamedUser(User);
+
+fn validate_named(user: &NamedUser) -> Result<(), &'static str> {
+    if user.0.name.is_empty() {
+        return Err("must have a name");
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Group {
+    #[json_api(relationship)]
+    owner: Arc<Admin>,
+}
+
+#[test]
+fn test_delegating_newtype() {
+    let document: Document<AdminUser> = deserialize_document(
+        r#"{ "data": { "type": "user", "id": "1", "attributes": { "name": "John Smith" } } }"#,
+    )
+    .unwrap();
+
+    assert_eq!(document.data.0.name, "John Smith");
+    assert_eq!(AdminUser::TYPE, "user");
+    assert_eq!(AdminUser::descriptor().kind, "user");
+}
+
+#[test]
+fn test_newtype_with_resource_type() {
+    let document: Document<Group> = deserialize_document(
+        r#"{
+            "data": {
+                "type": "group",
+                "relationships": { "owner": { "data": { "type": "admins", "id": "1" } } }
+            },
+            "included": [
+                { "type": "admins", "id": "1", "attributes": { "name": "John Smith" } }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(document.data.owner.0.id, "1");
+    assert_eq!(document.data.owner.0.name, "John Smith");
+    assert_eq!(Admin::TYPE, "admins");
+    assert_eq!(Admin::descriptor().kind, "admins");
+    assert!(Admin::descriptor().attribute("name").is_some());
+}
+
+#[test]
+fn test_newtype_type_mismatch() {
+    let error = deserialize_document::<Admin>(
+        r#"{ "data": { "type": "user", "id": "1", "attributes": { "name": "John Smith" } } }"#,
+    )
+    .unwrap_err();
+
+    match error {
+        Error::DeserializeError(DeserializeError::ResourceTypeMismatch { expected, found }) => {
+            assert_eq!(expected, "admins");
+            assert_eq!(found, "user");
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_newtype_validation() {
+    let document: Document<NamedUser> = deserialize_document(
+        r#"{ "data": { "type": "user", "id": "1", "attributes": { "name": "John Smith" } } }"#,
+    )
+    .unwrap();
+    assert_eq!(document.data.0.name, "John Smith");
+
+    let error = deserialize_document::<NamedUser>(
+        r#"{ "data": { "type": "user", "id": "1", "attributes": { "name": "" } } }"#,
+    )
+    .unwrap_err();
+
+    match error {
+        Error::DeserializeError(DeserializeError::Validation { pointer, message }) => {
+            assert_eq!(pointer, "/data");
+            assert_eq!(message, "must have a name");
+        }
+        error => panic!("Expected a validation error, but got {:?}", error),
+    }
+}
+
+#[derive(Debug)]
+struct Note {
+    text: String,
+}
+
+impl JsonApiDeserialize for Note {
+    fn from_value(value: &Value, _: &mut IncludedMap) -> Result<Self, DeserializeError> {
+        Ok(Note {
+            text: serde_json::from_value(value["attributes"]["text"].clone())?,
+        })
+    }
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct PinnedNote(Note);
+
+#[test]
+fn test_newtype_of_hand_written_resource() {
+    let document: Document<PinnedNote> = deserialize_document(
+        r#"{ "data": { "type": "note", "id": "1", "attributes": { "text": "Foo" } } }"#,
+    )
+    .unwrap();
+
+    assert_eq!(document.data.0.text, "Foo");
+    assert!(PinnedNote::DESCRIPTOR.is_none());
+    assert!(AdminUser::DESCRIPTOR.is_some());
+}
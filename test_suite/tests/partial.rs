@@ -0,0 +1,95 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, deserialize_document_partial, DeserializeError, Document, Error,
+    JsonApiDeserialize,
+};
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+    #[json_api(relationship = "single", resource = "Person")]
+    author: Arc<Person>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+const DOCUMENT: &str = r#"{
+    "data": [
+        {
+            "id": "1",
+            "type": "article",
+            "attributes": { "title": "Foo" },
+            "relationships": {
+                "author": { "data": { "type": "person", "id": "p-1" } }
+            }
+        },
+        {
+            "id": "2",
+            "type": "article",
+            "attributes": { "title": 2 },
+            "relationships": {
+                "author": { "data": { "type": "person", "id": "p-1" } }
+            }
+        },
+        {
+            "id": "3",
+            "type": "article",
+            "attributes": { "title": "Bar" },
+            "relationships": {
+                "author": { "data": { "type": "person", "id": "p-2" } }
+            }
+        }
+    ],
+    "included": [
+        {
+            "id": "p-1",
+            "type": "person",
+            "attributes": { "name": "John Smith" }
+        }
+    ]
+}"#;
+
+#[test]
+fn test_partial_document() {
+    let document: Document<Vec<Result<Article, DeserializeError>>> =
+        deserialize_document_partial(DOCUMENT).unwrap();
+
+    assert_eq!(document.data.len(), 3);
+    assert_eq!(document.data[0].as_ref().unwrap().title, "Foo");
+    assert!(matches!(
+        document.data[1],
+        Err(DeserializeError::SerdeError(_))
+    ));
+    assert!(matches!(
+        document.data[2],
+        Err(DeserializeError::MissingResource { .. })
+    ));
+}
+
+#[test]
+fn test_result_items() {
+    let document: Document<Vec<Result<Article, DeserializeError>>> =
+        deserialize_document(DOCUMENT).unwrap();
+
+    let (articles, skipped): (Vec<_>, Vec<_>) = document.data.into_iter().partition(Result::is_ok);
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(skipped.len(), 2);
+}
+
+#[test]
+fn test_partial_requires_array() {
+    let result: Result<Document<Vec<Result<Article, DeserializeError>>>, Error> =
+        deserialize_document_partial(r#"{"data": {"id": "1", "type": "article"}}"#);
+
+    assert!(matches!(
+        result,
+        Err(Error::DeserializeError(DeserializeError::InvalidType(_)))
+    ));
+}
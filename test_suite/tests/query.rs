@@ -0,0 +1,121 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    DeserializeError, IncludedMap, JsonApiDeserialize, QueryBuilder, Reference,
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+    #[json_api(relationship = "single", resource = "Person")]
+    author: Arc<Person>,
+    #[json_api(relationship = "multiple", resource = "Comment")]
+    comments: Vec<Arc<Comment>>,
+    #[json_api(relationship = "multiple")]
+    tags: Vec<Reference>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    id: String,
+    #[json_api(rename = "full-name")]
+    name: String,
+    #[json_api(relationship = "optional", resource = "Article")]
+    featured_article: Option<Arc<Article>>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Comment {
+    id: String,
+    body: String,
+    #[json_api(relationship = "single", resource = "Person")]
+    author: Arc<Person>,
+}
+
+#[test]
+fn test_include_paths() {
+    let builder = QueryBuilder::for_resource::<Article>();
+
+    assert_eq!(
+        builder.include_paths(),
+        ["author.featuredArticle", "comments.author.featuredArticle"]
+    );
+}
+
+#[test]
+fn test_pairs() {
+    let pairs = QueryBuilder::for_resource::<Comment>()
+        .sort("-created")
+        .sort("body")
+        .filter("status", "published")
+        .page("number", "2")
+        .pairs();
+
+    let pairs: Vec<_> = pairs
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+
+    assert_eq!(
+        pairs,
+        [
+            (
+                "include",
+                "author.featuredArticle.author,author.featuredArticle.comments"
+            ),
+            ("fields[comment]", "body,author"),
+            ("fields[person]", "full-name,featuredArticle"),
+            ("fields[article]", "title,author,comments,tags"),
+            ("sort", "-created,body"),
+            ("filter[status]", "published"),
+            ("page[number]", "2"),
+        ]
+    );
+}
+
+#[test]
+fn test_build() {
+    #[derive(Debug, JsonApiDeserialize)]
+    struct Tag {
+        id: String,
+        label: String,
+    }
+
+    assert_eq!(
+        QueryBuilder::for_resource::<Tag>()
+            .page("size", "10")
+            .build(),
+        "fields%5Btag%5D=label&page%5Bsize%5D=10"
+    );
+}
+
+#[test]
+fn test_hand_written_related_resource() {
+    #[derive(Debug)]
+    struct Avatar {
+        url: String,
+    }
+
+    impl JsonApiDeserialize for Avatar {
+        fn from_value(value: &Value, _: &mut IncludedMap) -> Result<Self, DeserializeError> {
+            Ok(Avatar {
+                url: serde_json::from_value(value["attributes"]["url"].clone())?,
+            })
+        }
+    }
+
+    #[derive(Debug, JsonApiDeserialize)]
+    struct Profile {
+        id: String,
+        #[json_api(relationship, resource = "Avatar")]
+        avatar: Arc<Avatar>,
+    }
+
+    let builder = QueryBuilder::for_resource::<Profile>();
+
+    assert!(builder.include_paths().is_empty());
+    assert_eq!(builder.build(), "fields%5Bprofile%5D=avatar");
+}
@@ -54,14 +54,16 @@ fn test_deserialize() {
         document.data.author,
         Reference {
             kind: "person".to_string(),
-            id: "p-1".to_string()
+            id: "p-1".to_string(),
+            meta: None
         }
     );
     assert_eq!(
         document.data.reviewer,
         Some(Reference {
             kind: "person".to_string(),
-            id: "p-2".to_string()
+            id: "p-2".to_string(),
+            meta: None
         })
     );
     assert!(document.data.publisher.is_none());
@@ -69,14 +71,16 @@ fn test_deserialize() {
         document.data.comments.first().cloned().unwrap(),
         Reference {
             kind: "comment".to_string(),
-            id: "c-1".to_string()
+            id: "c-1".to_string(),
+            meta: None
         }
     );
     assert_eq!(
         document.data.comments.last().cloned().unwrap(),
         Reference {
             kind: "comment".to_string(),
-            id: "c-2".to_string()
+            id: "c-2".to_string(),
+            meta: None
         }
     );
 }
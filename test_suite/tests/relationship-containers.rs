@@ -0,0 +1,123 @@
+#![allow(unused)]
+
+use indexmap::IndexMap;
+use jsonapi_deserialize::{deserialize_document, Document, JsonApiDeserialize, Reference};
+use smallvec::SmallVec;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    #[json_api(relationship = "multiple")]
+    tags: HashSet<Reference>,
+    #[json_api(relationship = "multiple")]
+    categories: BTreeSet<Reference>,
+    #[json_api(relationship = "multiple", resource = "Person")]
+    authors: BTreeMap<String, Arc<Person>>,
+    #[json_api(relationship = "multiple", resource = "Person")]
+    editors: IndexMap<String, Arc<Person>>,
+    #[json_api(relationship = "multiple", resource = "Person")]
+    reviewers: SmallVec<[Arc<Person>; 2]>,
+    #[json_api(relationship = "multiple", resource = "Person")]
+    translators: Box<[Arc<Person>]>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+const PEOPLE: &str = r#"[
+    { "type": "person", "id": "p-1" },
+    { "type": "person", "id": "p-2" }
+]"#;
+
+fn document() -> String {
+    format!(
+        r#"{{
+            "data": {{
+                "id": "1",
+                "type": "article",
+                "relationships": {{
+                    "tags": {{
+                        "data": [
+                            {{ "type": "tag", "id": "t-1" }},
+                            {{ "type": "tag", "id": "t-1" }}
+                        ]
+                    }},
+                    "categories": {{
+                        "data": [
+                            {{ "type": "category", "id": "c-2" }},
+                            {{ "type": "category", "id": "c-1" }}
+                        ]
+                    }},
+                    "authors": {{ "data": {PEOPLE} }},
+                    "editors": {{ "data": {PEOPLE} }},
+                    "reviewers": {{ "data": {PEOPLE} }},
+                    "translators": {{ "data": {PEOPLE} }}
+                }}
+            }},
+            "included": [
+                {{ "type": "person", "id": "p-1", "attributes": {{ "name": "John Smith" }} }},
+                {{ "type": "person", "id": "p-2", "attributes": {{ "name": "Jane Doe" }} }}
+            ]
+        }}"#
+    )
+}
+
+#[test]
+fn test_reference_sets() {
+    let document: Document<Article> = deserialize_document(&document()).unwrap();
+
+    assert_eq!(document.data.tags.len(), 1);
+    assert_eq!(
+        document
+            .data
+            .categories
+            .iter()
+            .map(|category| category.id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["c-1", "c-2"]
+    );
+}
+
+#[test]
+fn test_resource_maps_keyed_by_id() {
+    let document: Document<Article> = deserialize_document(&document()).unwrap();
+
+    assert_eq!(document.data.authors["p-2"].name, "Jane Doe");
+    assert_eq!(
+        document.data.editors.keys().collect::<Vec<_>>(),
+        vec!["p-1", "p-2"]
+    );
+    assert!(Arc::ptr_eq(
+        &document.data.authors["p-1"],
+        &document.data.editors["p-1"]
+    ));
+}
+
+#[test]
+fn test_resource_sequences() {
+    let document: Document<Article> = deserialize_document(&document()).unwrap();
+
+    assert!(!document.data.reviewers.spilled());
+    assert_eq!(document.data.reviewers[1].name, "Jane Doe");
+    assert_eq!(document.data.translators[0].name, "John Smith");
+}
+
+#[test]
+fn test_primary_data_containers() {
+    let json = r#"{
+        "data": [
+            { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } },
+            { "type": "person", "id": "p-2", "attributes": { "name": "Jane Doe" } }
+        ]
+    }"#;
+
+    let document: Document<VecDeque<Person>> = deserialize_document(json).unwrap();
+    assert_eq!(document.data.back().unwrap().name, "Jane Doe");
+
+    let document: Document<Box<[Person]>> = deserialize_document(json).unwrap();
+    assert_eq!(document.data.len(), 2);
+}
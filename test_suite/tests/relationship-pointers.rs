@@ -0,0 +1,111 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{deserialize_document, Document, IncludedMap, JsonApiDeserialize};
+use serde_json::Value;
+use std::rc::Rc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    #[json_api(relationship = "single", resource = "Person")]
+    author: Rc<Person>,
+    #[json_api(relationship = "optional", resource = "Person")]
+    reviewer: Option<Rc<Person>>,
+    #[json_api(relationship = "single", resource = "Person")]
+    editor: Box<Person>,
+    #[json_api(relationship = "multiple", resource = "Person")]
+    contributors: Vec<Person>,
+    #[json_api(relationship = "single", resource = "Person", default)]
+    translator: Option<Person>,
+    #[json_api(relationship = "multiple", resource = "Person", default)]
+    illustrators: Vec<Rc<Person>>,
+}
+
+#[derive(Clone, Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+const DOCUMENT: &str = r#"{
+    "data": {
+        "id": "1",
+        "type": "article",
+        "relationships": {
+            "author": { "data": { "type": "person", "id": "p-1" } },
+            "reviewer": { "data": { "type": "person", "id": "p-1" } },
+            "editor": { "data": { "type": "person", "id": "p-2" } },
+            "contributors": {
+                "data": [
+                    { "type": "person", "id": "p-1" },
+                    { "type": "person", "id": "p-2" }
+                ]
+            },
+            "translator": { "data": { "type": "person", "id": "p-2" } }
+        }
+    },
+    "included": [
+        { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } },
+        { "type": "person", "id": "p-2", "attributes": { "name": "Jane Doe" } }
+    ]
+}"#;
+
+#[test]
+fn test_rc_relationships_share_resources() {
+    let document: Document<Article> = deserialize_document(DOCUMENT).unwrap();
+    let reviewer = document.data.reviewer.unwrap();
+
+    assert_eq!(document.data.author.name, "John Smith");
+    assert!(Rc::ptr_eq(&document.data.author, &reviewer));
+}
+
+#[test]
+fn test_owned_relationships_are_cloned() {
+    let document: Document<Article> = deserialize_document(DOCUMENT).unwrap();
+
+    assert_eq!(document.data.editor.name, "Jane Doe");
+    assert_eq!(
+        document
+            .data
+            .contributors
+            .iter()
+            .map(|person| person.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["John Smith", "Jane Doe"]
+    );
+}
+
+#[test]
+fn test_default_relationships() {
+    let document: Document<Article> = deserialize_document(DOCUMENT).unwrap();
+
+    assert_eq!(document.data.translator.unwrap().name, "Jane Doe");
+    assert!(document.data.illustrators.is_empty());
+}
+
+#[test]
+fn test_rc_shared_within_from_value() {
+    let mut document: Value = serde_json::from_str(DOCUMENT).unwrap();
+    let mut included_map = IncludedMap::try_from(document["included"].take()).unwrap();
+    let article = Article::from_value(&document["data"], &mut included_map).unwrap();
+
+    assert!(Rc::ptr_eq(&article.author, &article.reviewer.unwrap()));
+}
+
+#[test]
+fn test_rc_shared_within_sharing() {
+    let mut document: Value = serde_json::from_str(DOCUMENT).unwrap();
+    let mut included_map = IncludedMap::try_from(document["included"].take()).unwrap();
+
+    let (first, second) = included_map.sharing(|included_map| {
+        (
+            included_map
+                .get_shared::<Rc<Person>, _>("person", "p-1")
+                .unwrap(),
+            included_map
+                .get_shared::<Rc<Person>, _>("person", "p-1")
+                .unwrap(),
+        )
+    });
+
+    assert!(Rc::ptr_eq(&first, &second));
+}
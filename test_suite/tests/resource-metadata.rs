@@ -0,0 +1,105 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{Cardinality, JsonApiDeserialize, JsonApiResource, Reference};
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+#[json_api(resource_type = "articles")]
+struct Article {
+    id: String,
+    #[json_api(rename = "headline")]
+    title: String,
+    #[json_api(default)]
+    view_count: u32,
+    #[json_api(optional)]
+    summary: Option<String>,
+    #[json_api(relationship = "single", resource = "Person")]
+    author: Arc<Person>,
+    #[json_api(optional, relationship = "optional")]
+    reviewer: Option<Option<Reference>>,
+    #[json_api(default, relationship = "multiple")]
+    tags: Vec<Reference>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    id: String,
+    name: String,
+}
+
+#[test]
+fn test_resource_type() {
+    assert_eq!(Article::TYPE, "articles");
+    assert_eq!(Person::TYPE, "person");
+    assert_eq!(Article::descriptor().kind, "articles");
+}
+
+#[test]
+fn test_attributes() {
+    let descriptor = Article::descriptor();
+    let attributes: Vec<_> = descriptor
+        .attributes
+        .iter()
+        .map(|attribute| {
+            (
+                attribute.field,
+                attribute.name,
+                attribute.default,
+                attribute.optional,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        attributes,
+        [
+            ("title", "headline", false, false),
+            ("view_count", "viewCount", true, false),
+            ("summary", "summary", false, true),
+        ]
+    );
+}
+
+#[test]
+fn test_relationships() {
+    let descriptor = Article::descriptor();
+
+    let author = descriptor.relationship("author").unwrap();
+    assert_eq!(author.cardinality, Cardinality::Single);
+    assert_eq!((author.resource.unwrap())().kind, "person");
+
+    let reviewer = descriptor.relationship("reviewer").unwrap();
+    assert_eq!(reviewer.cardinality, Cardinality::Optional);
+    assert!(reviewer.resource.is_none());
+    assert!(reviewer.optional);
+
+    let tags = descriptor.relationship("tags").unwrap();
+    assert_eq!(tags.cardinality, Cardinality::Multiple);
+    assert!(tags.default);
+}
+
+#[test]
+fn test_pointer_mapping() {
+    let descriptor = Article::descriptor();
+
+    assert_eq!(
+        descriptor.field_pointer("title").as_deref(),
+        Some("/attributes/headline")
+    );
+    assert_eq!(
+        descriptor.field_pointer("author").as_deref(),
+        Some("/relationships/author")
+    );
+    assert_eq!(descriptor.field_pointer("id"), None);
+
+    assert_eq!(
+        descriptor.pointer_field("/attributes/viewCount"),
+        Some("view_count")
+    );
+    assert_eq!(
+        descriptor.pointer_field("/relationships/tags/data/0"),
+        Some("tags")
+    );
+    assert_eq!(descriptor.pointer_field("/attributes/unknown"), None);
+    assert_eq!(descriptor.pointer_field("/data"), None);
+}
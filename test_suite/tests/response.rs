@@ -0,0 +1,184 @@
+#![allow(unused)]
+
+use http::{header, Response, StatusCode};
+use jsonapi_deserialize::{
+    deserialize_response, deserialize_response_with_options, Error, JsonApiDeserialize,
+    ResponseDocument, ResponseError, ResponseOptions,
+};
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+}
+
+const DOCUMENT: &str =
+    r#"{ "data": { "type": "article", "id": "1", "attributes": { "title": "Foo" } } }"#;
+
+fn response(status: StatusCode, content_type: &str, body: &str) -> Response<String> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(body.to_string())
+        .unwrap()
+}
+
+#[test]
+fn test_successful_response() {
+    let response = Response::builder()
+        .status(StatusCode::CREATED)
+        .header(header::CONTENT_TYPE, "application/vnd.api+json")
+        .header(header::LOCATION, "https://example.com/articles/1")
+        .header(header::ETAG, "\"abc\"")
+        .body(DOCUMENT.as_bytes())
+        .unwrap();
+
+    let response = deserialize_response::<Article, _>(response).unwrap();
+
+    assert_eq!(response.status, StatusCode::CREATED);
+    assert_eq!(
+        response.location.unwrap().to_string(),
+        "https://example.com/articles/1"
+    );
+    assert_eq!(response.etag.as_deref(), Some("\"abc\""));
+    assert_eq!(response.document.data.title, "Foo");
+}
+
+#[test]
+fn test_no_content_response() {
+    let response = Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Vec::new())
+        .unwrap();
+
+    let response = deserialize_response::<(), _>(response).unwrap();
+
+    assert_eq!(response.status, StatusCode::NO_CONTENT);
+}
+
+#[test]
+fn test_profile_parameter() {
+    let response = response(
+        StatusCode::OK,
+        r#"application/vnd.api+json; profile="https://example.com/profile""#,
+        DOCUMENT,
+    );
+
+    assert!(deserialize_response::<Article, _>(response).is_ok());
+}
+
+#[test]
+fn test_unsupported_media_type() {
+    for content_type in [
+        "application/json",
+        "application/vnd.api+json; charset=utf-8",
+        "not a media type",
+    ] {
+        let result =
+            deserialize_response::<Article, _>(response(StatusCode::OK, content_type, DOCUMENT));
+
+        assert!(matches!(
+            result,
+            Err(Error::ResponseError(ResponseError::UnsupportedMediaType(Some(found)))) if found == content_type
+        ));
+    }
+
+    let response = Response::new(DOCUMENT);
+    assert!(matches!(
+        deserialize_response::<Article, _>(response),
+        Err(Error::ResponseError(ResponseError::UnsupportedMediaType(
+            None
+        )))
+    ));
+}
+
+#[test]
+fn test_unsupported_extensions() {
+    let content_type =
+        r#"application/vnd.api+json; ext="https://jsonapi.org/ext/atomic https://example.com/ext""#;
+
+    let result =
+        deserialize_response::<Article, _>(response(StatusCode::OK, content_type, DOCUMENT));
+    let extensions = match result {
+        Err(Error::ResponseError(ResponseError::NotAcceptable { extensions })) => extensions,
+        result => panic!("Expected not acceptable, but got {:?}", result),
+    };
+    assert_eq!(
+        extensions,
+        ["https://jsonapi.org/ext/atomic", "https://example.com/ext"]
+    );
+
+    let options = ResponseOptions {
+        extensions: vec![
+            "https://jsonapi.org/ext/atomic".to_string(),
+            "https://example.com/ext".to_string(),
+        ],
+        ..Default::default()
+    };
+    let result: Result<ResponseDocument<Article>, _> = deserialize_response_with_options(
+        response(StatusCode::OK, content_type, DOCUMENT),
+        &options,
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_error_response() {
+    let result = deserialize_response::<Article, _>(response(
+        StatusCode::NOT_FOUND,
+        "application/vnd.api+json",
+        r#"{ "errors": [{ "status": "404", "title": "Not found" }] }"#,
+    ));
+
+    let (errors, status) = match result {
+        Err(Error::DocumentError(errors, status)) => (errors, status),
+        result => panic!("Expected DocumentError, but got {:?}", result),
+    };
+    assert_eq!(status, Some(StatusCode::NOT_FOUND));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].title.as_deref(), Some("Not found"));
+}
+
+#[test]
+fn test_error_response_without_document() {
+    let result = deserialize_response::<Article, _>(response(
+        StatusCode::BAD_GATEWAY,
+        "text/html",
+        "<h1>Bad Gateway</h1>",
+    ));
+
+    assert!(matches!(
+        result,
+        Err(Error::DocumentError(errors, Some(StatusCode::BAD_GATEWAY))) if errors.is_empty()
+    ));
+}
+
+#[test]
+fn test_successful_response_with_errors() {
+    let result = deserialize_response::<Article, _>(response(
+        StatusCode::OK,
+        "application/vnd.api+json",
+        r#"{ "errors": [{ "title": "Partially failed" }] }"#,
+    ));
+
+    assert!(matches!(
+        result,
+        Err(Error::DocumentError(errors, Some(StatusCode::OK))) if errors.len() == 1
+    ));
+}
+
+#[test]
+fn test_not_modified_response() {
+    let response = Response::builder()
+        .status(StatusCode::NOT_MODIFIED)
+        .header(header::ETAG, "\"abc\"")
+        .body(Vec::new())
+        .unwrap();
+
+    let result = deserialize_response::<Article, _>(response);
+
+    assert!(matches!(
+        result,
+        Err(Error::ResponseError(ResponseError::NotModified { etag: Some(etag) })) if etag == "\"abc\""
+    ));
+}
@@ -0,0 +1,193 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document_with_options, validate_document, DeserializeOptions, Document, Error,
+    JsonApiDeserialize, Rule,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+type Meta = HashMap<String, Value>;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+    #[json_api(relationship)]
+    author: Arc<Person>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+const VALID: &str = r#"{
+    "data": {
+        "type": "article",
+        "id": "1",
+        "attributes": { "title": "Foo", "tags": [{ "first-name": "x" }] },
+        "relationships": {
+            "author": { "data": { "type": "person", "id": "p-1" } },
+            "comments": { "links": { "related": "/articles/1/comments" } }
+        }
+    },
+    "included": [
+        { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } }
+    ]
+}"#;
+
+fn rules(json: &str) -> Vec<(Rule, String)> {
+    validate_document(json)
+        .into_iter()
+        .map(|violation| (violation.rule, violation.pointer))
+        .collect()
+}
+
+#[test]
+fn test_valid_document() {
+    assert!(validate_document(VALID).is_empty());
+    assert!(validate_document(r#"{ "meta": { "count": 1 } }"#).is_empty());
+    assert!(validate_document(r#"{ "errors": [{ "status": "404" }] }"#).is_empty());
+}
+
+#[test]
+fn test_top_level_violations() {
+    assert_eq!(rules("[]"), vec![(Rule::TopLevelObject, "".to_string())]);
+    assert_eq!(rules("{}"), vec![(Rule::TopLevelMembers, "".to_string())]);
+    assert_eq!(
+        rules(r#"{ "data": null, "errors": [] }"#),
+        vec![(Rule::DataAndErrors, "".to_string())]
+    );
+    assert_eq!(
+        rules(r#"{ "meta": {}, "included": [] }"#),
+        vec![(Rule::IncludedWithoutData, "/included".to_string())]
+    );
+    assert_eq!(
+        rules(r#"{ "data": 1 }"#),
+        vec![(Rule::PrimaryData, "/data".to_string())]
+    );
+}
+
+#[test]
+fn test_resource_violations() {
+    let json = r#"{
+        "data": [
+            { "type": "article", "id": 1 },
+            { "id": "2" },
+            {
+                "type": "article",
+                "id": "3",
+                "attributes": { "type": "x", "author": "y", "a.b": 1, "nested": { "links": {} } },
+                "relationships": {
+                    "author": {},
+                    "editor": { "data": [{ "type": "person" }] },
+                    "reviewer": { "data": "p-1" }
+                }
+            }
+        ]
+    }"#;
+
+    assert_eq!(
+        rules(json),
+        vec![
+            (Rule::ResourceIdentification, "/data/0/id".to_string()),
+            (Rule::ResourceIdentification, "/data/1".to_string()),
+            (Rule::MemberNames, "/data/2/attributes/a.b".to_string()),
+            (
+                Rule::ResourceFields,
+                "/data/2/attributes/author".to_string()
+            ),
+            (
+                Rule::ComplexAttributes,
+                "/data/2/attributes/nested/links".to_string()
+            ),
+            (Rule::ResourceFields, "/data/2/attributes/type".to_string()),
+            (
+                Rule::RelationshipMembers,
+                "/data/2/relationships/author".to_string()
+            ),
+            (
+                Rule::ResourceIdentifier,
+                "/data/2/relationships/editor/data/0".to_string()
+            ),
+            (
+                Rule::ResourceLinkage,
+                "/data/2/relationships/reviewer/data".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_compound_document_violations() {
+    let json = r#"{
+        "data": {
+            "type": "article",
+            "id": "1",
+            "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+        },
+        "included": [
+            { "type": "person", "id": "p-1" },
+            { "type": "person", "id": "p-1" },
+            {
+                "type": "person",
+                "id": "p-2",
+                "relationships": { "self": { "data": { "type": "person", "id": "p-2" } } }
+            }
+        ]
+    }"#;
+
+    assert_eq!(
+        rules(json),
+        vec![
+            (Rule::UniqueResources, "/included/1".to_string()),
+            (Rule::FullLinkage, "/included/2".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_linkage_document() {
+    let json = r#"{
+        "data": [{ "type": "person", "id": "p-1" }],
+        "included": [{ "type": "person", "id": "p-2" }]
+    }"#;
+
+    assert_eq!(
+        rules(json),
+        vec![(Rule::FullLinkage, "/included/0".to_string())]
+    );
+}
+
+#[test]
+fn test_validate_option() {
+    let options = DeserializeOptions {
+        validate: true,
+        ..Default::default()
+    };
+
+    let document: Document<Article> = deserialize_document_with_options(VALID, &options).unwrap();
+    assert_eq!(document.data.author.name, "John Smith");
+
+    let json = r#"{
+        "data": { "type": "article", "id": "1", "attributes": { "title": "Foo" } },
+        "included": [{ "type": "person", "id": "p-1" }]
+    }"#;
+    let error = deserialize_document_with_options::<Article, Meta>(json, &options).unwrap_err();
+
+    match error {
+        Error::SpecViolation(violations) => {
+            assert_eq!(violations.len(), 1);
+            assert_eq!(violations[0].rule, Rule::FullLinkage);
+            assert_eq!(
+                violations[0].to_string(),
+                "Included resource person p-1 is not identified anywhere in the document at \
+                 /included/0 (full-linkage, see \
+                 https://jsonapi.org/format/1.1/#document-compound-documents)"
+            );
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
@@ -0,0 +1,181 @@
+#![allow(unused)]
+
+use futures::executor::block_on;
+use futures::io::Cursor;
+use futures::StreamExt;
+use jsonapi_deserialize::{
+    deserialize_document_iter, deserialize_document_stream, DeserializeError, DocumentIter,
+    DocumentStream, Error, IncludedMap, JsonApiDeserialize,
+};
+use std::io::Read;
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+    #[json_api(relationship = "single", resource = "Person")]
+    author: Arc<Person>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+const INCLUDED_FIRST: &str = r#"{
+    "meta": { "total": 2 },
+    "included": [
+        { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } }
+    ],
+    "data": [
+        {
+            "id": "1",
+            "type": "article",
+            "attributes": { "title": "Foo" },
+            "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+        },
+        {
+            "id": "2",
+            "type": "article",
+            "attributes": { "title": "Bar" },
+            "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+        }
+    ]
+}"#;
+
+const INCLUDED_LAST: &str = r#"{
+    "data": [
+        {
+            "id": "1",
+            "type": "article",
+            "attributes": { "title": "Foo" },
+            "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+        },
+        {
+            "id": "2",
+            "type": "article",
+            "attributes": { "title": 2 },
+            "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+        }
+    ],
+    "included": [
+        { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } }
+    ],
+    "links": { "next": "/articles?page=2" }
+}"#;
+
+/// Reader which only returns a few bytes at a time.
+struct SlowReader<'a>(&'a [u8]);
+
+impl Read for SlowReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let length = buf.len().min(self.0.len()).min(3);
+        buf[..length].copy_from_slice(&self.0[..length]);
+        self.0 = &self.0[length..];
+        Ok(length)
+    }
+}
+
+#[test]
+fn test_iter_included_first() {
+    let mut iter = deserialize_document_iter::<Article, _>(SlowReader(INCLUDED_FIRST.as_bytes()));
+
+    assert!(iter.meta().is_none());
+
+    let first = iter.next().unwrap().unwrap();
+    assert_eq!(iter.meta().unwrap()["total"], 2);
+    assert_eq!(first.title, "Foo");
+    assert_eq!(first.author.name, "John Smith");
+
+    let second = iter.next().unwrap().unwrap();
+    assert_eq!(second.title, "Bar");
+    assert!(Arc::ptr_eq(&first.author, &second.author));
+
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn test_iter_included_last() {
+    let mut iter = deserialize_document_iter::<Article, _>(SlowReader(INCLUDED_LAST.as_bytes()));
+
+    let first = iter.next().unwrap().unwrap();
+    assert_eq!(first.author.name, "John Smith");
+
+    assert!(matches!(
+        iter.next(),
+        Some(Err(Error::DeserializeError(DeserializeError::SerdeError(
+            _
+        ))))
+    ));
+    assert!(iter.next().is_none());
+    assert_eq!(
+        iter.links().unwrap().next.as_ref().unwrap().href,
+        "/articles?page=2"
+    );
+}
+
+#[test]
+fn test_iter_without_included() {
+    #[derive(Debug, JsonApiDeserialize)]
+    #[json_api(resource_type = "article")]
+    struct Summary {
+        id: String,
+    }
+
+    let ids: Vec<_> = deserialize_document_iter::<Summary, _>(INCLUDED_LAST.as_bytes())
+        .map(|summary| summary.unwrap().id)
+        .collect();
+
+    assert_eq!(ids, ["1", "2"]);
+}
+
+#[test]
+fn test_iter_single_resource() {
+    let json = r#"{"data": {"type": "person", "id": "p-1", "attributes": {"name": "Jane"}}}"#;
+    let people: Vec<_> = deserialize_document_iter::<Person, _>(json.as_bytes()).collect();
+
+    assert_eq!(people.len(), 1);
+    assert_eq!(people[0].as_ref().unwrap().name, "Jane");
+}
+
+#[test]
+fn test_iter_document_errors() {
+    let json = r#"{"errors": [{"status": "500"}]}"#;
+    let mut iter = deserialize_document_iter::<Article, _>(json.as_bytes());
+
+    assert!(matches!(
+        iter.next(),
+        Some(Err(Error::DocumentError(_, None)))
+    ));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn test_iter_truncated_document() {
+    let json = &INCLUDED_FIRST[..INCLUDED_FIRST.len() - 20];
+    let results: Vec<_> = deserialize_document_iter::<Article, _>(json.as_bytes()).collect();
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
+
+#[test]
+fn test_stream() {
+    let stream = deserialize_document_stream::<Article, _>(Cursor::new(INCLUDED_LAST));
+    let results: Vec<_> = block_on(stream.collect());
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap().author.name, "John Smith");
+    assert!(results[1].is_err());
+}
+
+#[test]
+fn test_stream_is_send() {
+    fn assert_send<T: Send>() {}
+
+    assert_send::<IncludedMap>();
+    assert_send::<DocumentIter<Article, std::io::Cursor<Vec<u8>>>>();
+    assert_send::<DocumentStream<Article, Cursor<Vec<u8>>>>();
+}
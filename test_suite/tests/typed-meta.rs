@@ -0,0 +1,125 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, deserialize_document_value_with_options,
+    deserialize_document_with_options, DeserializeError, DeserializeOptions, Document, Error,
+    JsonApiDeserialize, JsonApiDocument,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Pagination {
+    total: u32,
+    page_count: u32,
+}
+
+const DOCUMENT: &str = r#"{
+    "meta": { "total": 42, "pageCount": 5 },
+    "data": [
+        { "type": "article", "id": "1", "attributes": { "title": "Foo" } }
+    ]
+}"#;
+
+#[test]
+fn test_typed_meta() {
+    let document: Document<Vec<Article>, Pagination> =
+        deserialize_document_with_options(DOCUMENT, &DeserializeOptions::default()).unwrap();
+
+    assert_eq!(
+        document.meta,
+        Some(Pagination {
+            total: 42,
+            page_count: 5
+        })
+    );
+    assert_eq!(document.data.len(), 1);
+}
+
+#[test]
+fn test_untyped_meta() {
+    let document: Document<Vec<Article>> = deserialize_document(DOCUMENT).unwrap();
+
+    assert_eq!(document.meta.unwrap()["total"], json!(42));
+}
+
+#[test]
+fn test_absent_meta() {
+    let document: Document<Vec<Article>, Pagination> =
+        deserialize_document_with_options(r#"{ "data": [] }"#, &DeserializeOptions::default())
+            .unwrap();
+
+    assert!(document.meta.is_none());
+}
+
+#[test]
+fn test_invalid_meta() {
+    let error = deserialize_document_with_options::<Vec<Article>, Pagination>(
+        r#"{ "meta": { "total": "many" }, "data": [] }"#,
+        &DeserializeOptions::default(),
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::DeserializeError(DeserializeError::SerdeError(_))
+    ));
+}
+
+#[test]
+fn test_typed_meta_with_options() {
+    let options = DeserializeOptions {
+        accumulate_errors: true,
+        validate: true,
+        ..Default::default()
+    };
+    let document: Document<Vec<Article>, Pagination> =
+        deserialize_document_value_with_options(serde_json::from_str(DOCUMENT).unwrap(), &options)
+            .unwrap();
+
+    assert_eq!(document.meta.unwrap().total, 42);
+}
+
+#[test]
+fn test_typed_meta_in_embedded_document() {
+    let document: JsonApiDocument<Vec<Article>, Pagination> =
+        serde_json::from_str(DOCUMENT).unwrap();
+
+    assert_eq!(document.meta.as_ref().unwrap().page_count, 5);
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct RateLimit {
+    retry_after: u32,
+}
+
+#[test]
+fn test_typed_error_meta() {
+    let error = deserialize_document::<Article>(
+        r#"{
+            "errors": [
+                { "status": "429", "meta": { "retry_after": 30 } },
+                { "status": "500" }
+            ]
+        }"#,
+    )
+    .unwrap_err();
+
+    let errors = match error {
+        Error::DocumentError(errors, _) => errors,
+        error => panic!("unexpected error: {:?}", error),
+    };
+
+    assert_eq!(
+        errors[0].meta_as::<RateLimit>().unwrap(),
+        Some(RateLimit { retry_after: 30 })
+    );
+    assert_eq!(errors[1].meta_as::<RateLimit>().unwrap(), None);
+}
@@ -0,0 +1,13 @@
+use jsonapi_deserialize::{JsonApiDeserialize, Reference};
+
+#[derive(JsonApiDeserialize)]
+struct Venue {
+    #[json_api(path = "address..lat")]
+    latitude: f64,
+    #[json_api(rename = "lng", path = "address.geo.lng")]
+    longitude: f64,
+    #[json_api(relationship, path = "owner.data")]
+    owner: Reference,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use jsonapi_deserialize::JsonApiDeserialize;
+use std::sync::Arc;
+
+#[derive(JsonApiDeserialize)]
+struct Article {
+    #[json_api(relationship = "single")]
+    authors: Vec<Arc<Person>>,
+    #[json_api(relationship = "multiple")]
+    reviewer: Option<Arc<Person>>,
+}
+
+#[derive(JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+fn main() {}
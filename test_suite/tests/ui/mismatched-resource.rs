@@ -0,0 +1,22 @@
+use jsonapi_deserialize::{JsonApiDeserialize, Reference};
+use std::sync::Arc;
+
+#[derive(JsonApiDeserialize)]
+struct Article {
+    #[json_api(relationship, resource = "Comment")]
+    author: Arc<Person>,
+    #[json_api(relationship, resource = "Person")]
+    reviewer: Reference,
+}
+
+#[derive(JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+#[derive(JsonApiDeserialize)]
+struct Comment {
+    content: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use jsonapi_deserialize::JsonApiDeserialize;
+
+#[derive(JsonApiDeserialize)]
+struct User {
+    name: String,
+}
+
+#[derive(JsonApiDeserialize)]
+struct AdminUser(#[json_api(rename = "user")] User);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use jsonapi_deserialize::JsonApiDeserialize;
+
+#[derive(JsonApiDeserialize)]
+struct User {
+    name: String,
+}
+
+#[derive(JsonApiDeserialize)]
+struct Pair(User, User);
+
+fn main() {}
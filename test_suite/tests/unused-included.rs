@@ -0,0 +1,72 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, deserialize_document_with_options, DeserializeOptions, Document,
+    JsonApiDeserialize, Reference,
+};
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    #[json_api(relationship)]
+    author: Arc<Person>,
+    #[json_api(relationship)]
+    comments: Vec<Reference>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+const DOCUMENT: &str = r#"{
+    "data": [
+        {
+            "type": "article",
+            "id": "1",
+            "relationships": {
+                "author": { "data": { "type": "person", "id": "p-1" } },
+                "comments": { "data": [{ "type": "comment", "id": "c-1" }] }
+            }
+        },
+        {
+            "type": "article",
+            "id": "2",
+            "relationships": {
+                "author": { "data": { "type": "person", "id": "p-1" } },
+                "comments": { "data": [] }
+            }
+        }
+    ],
+    "included": [
+        { "type": "comment", "id": "c-1", "attributes": { "content": "Lorem Ipsum" } },
+        { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } },
+        { "type": "person", "id": "p-2", "attributes": { "name": "Jane Doe" } }
+    ]
+}"#;
+
+#[test]
+fn test_unused_included() {
+    let options = DeserializeOptions {
+        report_unused_included: true,
+        ..Default::default()
+    };
+    let document: Document<Vec<Article>> =
+        deserialize_document_with_options(DOCUMENT, &options).unwrap();
+
+    assert_eq!(
+        document.unused_included,
+        Some(vec![
+            Reference::new("comment", "c-1"),
+            Reference::new("person", "p-2"),
+        ])
+    );
+}
+
+#[test]
+fn test_unused_included_not_requested() {
+    let document: Document<Vec<Article>> = deserialize_document(DOCUMENT).unwrap();
+
+    assert!(document.unused_included.is_none());
+}
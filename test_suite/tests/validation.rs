@@ -0,0 +1,110 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document, deserialize_document_with_options, DeserializeError, DeserializeOptions,
+    Document, Error, JsonApiDeserialize,
+};
+
+#[derive(Debug, JsonApiDeserialize)]
+#[json_api(validate = "validate_event")]
+struct Event {
+    id: String,
+    #[json_api(validate = "validate_title")]
+    title: String,
+    start: u32,
+    end: u32,
+}
+
+fn validate_title(title: &str) -> Result<(), &'static str> {
+    match title.is_empty() {
+        true => Err("title must not be empty"),
+        false => Ok(()),
+    }
+}
+
+fn validate_event(event: &Event) -> Result<(), String> {
+    match event.end < event.start {
+        true => Err(format!("event {} ends before it starts", event.id)),
+        false => Ok(()),
+    }
+}
+
+fn event(id: &str, title: &str, start: u32, end: u32) -> String {
+    format!(
+        r#"{{
+            "id": "{id}",
+            "type": "event",
+            "attributes": {{ "title": "{title}", "start": {start}, "end": {end} }}
+        }}"#
+    )
+}
+
+#[test]
+fn test_valid_resource() {
+    let json = format!(r#"{{ "data": {} }}"#, event("1", "Launch", 1, 2));
+    let document: Document<Event> = deserialize_document(&json).unwrap();
+
+    assert_eq!(document.data.title, "Launch");
+}
+
+#[test]
+fn test_field_validation() {
+    let json = format!(r#"{{ "data": {} }}"#, event("1", "", 1, 2));
+    let error = deserialize_document::<Event>(&json).unwrap_err();
+
+    match error {
+        Error::DeserializeError(DeserializeError::Validation { pointer, message }) => {
+            assert_eq!(pointer, "/data/attributes/title");
+            assert_eq!(message, "title must not be empty");
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_resource_validation() {
+    let json = format!(r#"{{ "data": {} }}"#, event("1", "Launch", 2, 1));
+    let error = deserialize_document::<Event>(&json).unwrap_err();
+
+    match error {
+        Error::DeserializeError(DeserializeError::Validation { pointer, message }) => {
+            assert_eq!(pointer, "/data");
+            assert_eq!(message, "event 1 ends before it starts");
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_accumulated_validation() {
+    let json = format!(
+        r#"{{ "data": [{}, {}, {}] }}"#,
+        event("1", "", 1, 2),
+        event("2", "Launch", 1, 2),
+        event("3", "Landing", 2, 1)
+    );
+    let options = DeserializeOptions {
+        accumulate_errors: true,
+        ..Default::default()
+    };
+    let result: Result<Document<Vec<Event>>, _> =
+        deserialize_document_with_options(&json, &options);
+    let error = result.unwrap_err();
+
+    let errors = match error {
+        Error::DeserializeError(DeserializeError::Multiple(errors)) => errors,
+        error => panic!("unexpected error: {:?}", error),
+    };
+
+    assert_eq!(
+        errors
+            .iter()
+            .map(|error| error.pointer.as_str())
+            .collect::<Vec<_>>(),
+        vec!["/data/0/attributes/title", "/data/2"]
+    );
+    assert!(matches!(
+        errors[1].error,
+        DeserializeError::Validation { .. }
+    ));
+}
@@ -0,0 +1,74 @@
+#![allow(unused)]
+
+use jsonapi_deserialize::{
+    deserialize_document_value, Document, DuplicatePolicy, IncludedMap, JsonApiDeserialize,
+    RawDocument,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Article {
+    id: String,
+    title: String,
+    #[json_api(relationship)]
+    author: Arc<Person>,
+}
+
+#[derive(Debug, JsonApiDeserialize)]
+struct Person {
+    name: String,
+}
+
+fn envelope() -> Value {
+    json!({
+        "status": 200,
+        "body": {
+            "data": {
+                "type": "article",
+                "id": "1",
+                "attributes": { "title": "Foo" },
+                "relationships": { "author": { "data": { "type": "person", "id": "p-1" } } }
+            },
+            "included": [
+                { "type": "person", "id": "p-1", "attributes": { "name": "John Smith" } }
+            ]
+        }
+    })
+}
+
+#[test]
+fn test_deserialize_document_value() {
+    let mut envelope = envelope();
+    let document: Document<Article> = deserialize_document_value(envelope["body"].take()).unwrap();
+
+    assert_eq!(document.data.title, "Foo");
+    assert_eq!(document.data.author.name, "John Smith");
+}
+
+#[test]
+fn test_included_map_from_value() {
+    let mut envelope = envelope();
+    let mut included_map = IncludedMap::try_from(envelope["body"]["included"].take()).unwrap();
+    let article = Article::from_value(&envelope["body"]["data"], &mut included_map).unwrap();
+
+    assert_eq!(article.author.name, "John Smith");
+    assert!(Arc::ptr_eq(
+        &article.author,
+        &included_map.get::<Person>("person", "p-1").unwrap()
+    ));
+}
+
+#[test]
+fn test_raw_document() {
+    let mut envelope = envelope();
+    let raw_document: RawDocument = serde_json::from_value(envelope["body"].take()).unwrap();
+    let mut included_map = IncludedMap::new(
+        raw_document.included.unwrap_or_default(),
+        DuplicatePolicy::FirstWins,
+    );
+    let article = Article::from_value(&raw_document.data.unwrap(), &mut included_map).unwrap();
+
+    assert_eq!(article.id, "1");
+    assert_eq!(article.author.name, "John Smith");
+}